@@ -1,5 +1,5 @@
-for i in $(seq 1 25); do
-    curl 'https://adventofcode.com/2024/day/'$i'/input' \
-        -H 'cookie: session='$SESSION \
-        > input/day$i.txt
-done
+#!/bin/bash
+
+cargo build --release
+
+./target/release/aoc24 fetch --year ${YEAR:-2024}
@@ -2,7 +2,4 @@
 
 cargo build --release
 
-time for i in $(seq 1 25); do
-    echo day $i:
-    ./target/release/aoc24 $i
-done
+./target/release/aoc24 all
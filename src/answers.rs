@@ -0,0 +1,259 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{read_to_string, write},
+    io::ErrorKind,
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{
+    days::{day::Part, error::Error},
+    runner::{Answers, DayRun},
+};
+
+/// Known good answers, stored as a small subset of TOML:
+///
+/// ```toml
+/// [day1]
+/// part1 = "11"
+/// part2 = "31"
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AnswerFile {
+    days: BTreeMap<i32, Answers>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing,
+}
+
+impl AnswerFile {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match read_to_string(path) {
+            Ok(contents) => contents.parse(),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(AnswerFile::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        Ok(write(path, self.to_string())?)
+    }
+
+    pub fn get(&self, day: i32) -> Option<&Answers> {
+        self.days.get(&day)
+    }
+
+    pub fn record(&mut self, day: i32, answers: &Answers) {
+        let recorded = self.days.entry(day).or_default();
+        if answers.part1.is_some() {
+            recorded.part1.clone_from(&answers.part1);
+        }
+        if answers.part2.is_some() {
+            recorded.part2.clone_from(&answers.part2);
+        }
+    }
+
+    /// Compares every part that was solved against its recorded answer.
+    pub fn check(&self, day: i32, answers: &Answers) -> Vec<(Part, Verdict)> {
+        let recorded = self.days.get(&day);
+        [
+            (
+                Part::One,
+                &answers.part1,
+                recorded.and_then(|r| r.part1.as_ref()),
+            ),
+            (
+                Part::Two,
+                &answers.part2,
+                recorded.and_then(|r| r.part2.as_ref()),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(part, actual, expected)| {
+            let actual = actual.as_ref()?;
+            let verdict = match expected {
+                None => Verdict::Missing,
+                Some(expected) if expected == actual => Verdict::Pass,
+                Some(expected) => Verdict::Fail {
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                },
+            };
+            Some((part, verdict))
+        })
+        .collect()
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Missing => write!(f, "MISSING"),
+            Verdict::Fail { expected, actual } => {
+                write!(f, "FAIL (expected {}, got {})", expected, actual)
+            }
+        }
+    }
+}
+
+/// Describes the verdict for every solved part, along with whether any of them failed.
+pub fn check_report(answer_file: &AnswerFile, runs: &[DayRun]) -> (String, bool) {
+    let mut report = String::new();
+    let mut failed = false;
+    for run in runs {
+        let Ok(answers) = &run.result else {
+            continue;
+        };
+        for (part, verdict) in answer_file.check(run.day, answers) {
+            failed |= matches!(verdict, Verdict::Fail { .. });
+            report.push_str(&format!("Day {} part {}: {}\n", run.day, part, verdict));
+        }
+    }
+    (report, failed)
+}
+
+impl FromStr for AnswerFile {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut days: BTreeMap<i32, Answers> = BTreeMap::new();
+        let mut current = None;
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: &str| Error::parse(line, message).at_line(i + 1);
+
+            if let Some(header) = line.strip_prefix('[') {
+                let day = header
+                    .strip_suffix(']')
+                    .and_then(|h| h.strip_prefix("day"))
+                    .and_then(|d| d.parse::<i32>().ok())
+                    .ok_or_else(|| error("expected a [dayN] header"))?;
+                days.entry(day).or_default();
+                current = Some(day);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected part = \"answer\""))?;
+            let value = unquote(value.trim()).ok_or_else(|| error("expected a quoted answer"))?;
+            let day = current.ok_or_else(|| error("answer outside of a [dayN] section"))?;
+            let answers = days.entry(day).or_default();
+            match key.trim() {
+                "part1" => answers.part1 = Some(value),
+                "part2" => answers.part2 = Some(value),
+                _ => return Err(error("expected part1 or part2")),
+            }
+        }
+
+        Ok(AnswerFile { days })
+    }
+}
+
+impl fmt::Display for AnswerFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (day, answers) in &self.days {
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+
+            writeln!(f, "[day{}]", day)?;
+            if let Some(part1) = &answers.part1 {
+                writeln!(f, "part1 = {}", quote(part1))?;
+            }
+            if let Some(part2) = &answers.part2 {
+                writeln!(f, "part2 = {}", quote(part2))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn unquote(s: &str) -> Option<String> {
+    let inner = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => value.push(chars.next()?),
+            '"' => return None,
+            c => value.push(c),
+        }
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(part1: Option<&str>, part2: Option<&str>) -> Answers {
+        Answers {
+            part1: part1.map(|s| s.to_owned()),
+            part2: part2.map(|s| s.to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut file = AnswerFile::default();
+        file.record(1, &answers(Some("11"), Some("31")));
+        file.record(17, &answers(Some("4,6,3"), None));
+        file.record(25, &answers(None, Some("say \"hi\"")));
+
+        let text = file.to_string();
+        assert_eq!(
+            text,
+            "[day1]\npart1 = \"11\"\npart2 = \"31\"\n\n[day17]\npart1 = \"4,6,3\"\n\n[day25]\npart2 = \"say \\\"hi\\\"\"\n"
+        );
+        assert_eq!(text.parse(), Ok(file));
+    }
+
+    #[test]
+    fn test_check() {
+        let file: AnswerFile = "# known answers\n[day3]\npart1 = \"161\"\n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            file.check(3, &answers(Some("161"), Some("48"))),
+            vec![(Part::One, Verdict::Pass), (Part::Two, Verdict::Missing)]
+        );
+        assert_eq!(
+            file.check(3, &answers(Some("160"), None)),
+            vec![(
+                Part::One,
+                Verdict::Fail {
+                    expected: "161".to_owned(),
+                    actual: "160".to_owned()
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_bad_line() {
+        let result = "[day3]\npart3 = \"1\"\n".parse::<AnswerFile>();
+        assert_eq!(
+            result,
+            Err(Error::parse("part3 = \"1\"", "expected part1 or part2").at_line(2))
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use std::{
+    collections::BTreeMap,
+    fs::{read_to_string, write},
+    io::ErrorKind,
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    days::{
+        day::{Context, Program},
+        error::Error,
+        Registry,
+    },
+    output::layout_table,
+    runner::{catch_panic, undefined_day, InputSource},
+};
+
+/// Summary of a set of timings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub mean: Duration,
+    pub median: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        if n == 0 {
+            return Stats {
+                mean: Duration::ZERO,
+                median: Duration::ZERO,
+                stddev: Duration::ZERO,
+            };
+        }
+
+        let mean = sorted.iter().sum::<Duration>() / n as u32;
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let variance = sorted
+            .iter()
+            .map(|s| (s.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        Stats {
+            mean,
+            median,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+pub struct DayBench {
+    pub day: i32,
+    pub result: Result<(Stats, Stats), Error>,
+}
+
+/// Times `iterations` runs of the program after one warm up run, separating parse
+/// time from the time taken to solve both parts.
+pub fn bench_day(
+    program: &dyn Program,
+    input: &str,
+    iterations: usize,
+) -> Result<(Stats, Stats), Error> {
+    let mut parse_times = Vec::with_capacity(iterations);
+    let mut solve_times = Vec::with_capacity(iterations);
+
+    let ctx = Context::default();
+    for i in 0..=iterations {
+        let start = Instant::now();
+        let solver = program.prepare(input)?;
+        let parsed = Instant::now();
+        solver.part1(&ctx)?;
+        solver.part2(&ctx)?;
+        let solved = Instant::now();
+
+        if i > 0 {
+            parse_times.push(parsed - start);
+            solve_times.push(solved - parsed);
+        }
+    }
+
+    Ok((Stats::new(&parse_times), Stats::new(&solve_times)))
+}
+
+/// Benchmarks the days one after another, so that they don't compete for CPU time.
+pub fn bench_days(
+    programs: &Registry,
+    year: i32,
+    days: &[i32],
+    source: &InputSource,
+    iterations: usize,
+) -> Vec<DayBench> {
+    days.iter()
+        .map(|day| {
+            let result = programs
+                .get(&(year, *day))
+                .ok_or_else(|| undefined_day(year, *day))
+                .and_then(|program| {
+                    let input = source.read(year, *day)?;
+                    catch_panic(|| bench_day(program.as_ref(), &input, iterations))
+                });
+            DayBench { day: *day, result }
+        })
+        .collect()
+}
+
+/// Mean parse and solve times from an earlier benchmark, stored as CSV:
+///
+/// ```csv
+/// day,parse_ns,solve_ns
+/// 1,21000,5100
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Baseline {
+    days: BTreeMap<i32, (Duration, Duration)>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match read_to_string(path) {
+            Ok(contents) => contents.parse(),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Baseline::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut csv = "day,parse_ns,solve_ns\n".to_owned();
+        for (day, (parse, solve)) in &self.days {
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                day,
+                parse.as_nanos(),
+                solve.as_nanos()
+            ));
+        }
+        Ok(write(path, csv)?)
+    }
+
+    pub fn record(&mut self, benches: &[DayBench]) {
+        for bench in benches {
+            if let Ok((parse, solve)) = &bench.result {
+                self.days.insert(bench.day, (parse.mean, solve.mean));
+            }
+        }
+    }
+
+    /// Relative change of the total mean time against the baseline, e.g. `0.1` for 10% slower.
+    pub fn change(&self, day: i32, parse: &Stats, solve: &Stats) -> Option<f64> {
+        let (base_parse, base_solve) = self.days.get(&day)?;
+        let base = (*base_parse + *base_solve).as_secs_f64();
+        let now = (parse.mean + solve.mean).as_secs_f64();
+        (base > 0.0).then(|| now / base - 1.0)
+    }
+}
+
+impl FromStr for Baseline {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut days = BTreeMap::new();
+        for (i, line) in s.lines().enumerate().skip(1) {
+            let error = |message: &str| Error::parse(line, message).at_line(i + 1);
+            let fields: Vec<u64> = line
+                .split(',')
+                .map(|f| f.trim().parse())
+                .collect::<Result<_, _>>()
+                .map_err(|_| error("expected day,parse_ns,solve_ns"))?;
+            let [day, parse, solve] = fields[..] else {
+                return Err(error("expected day,parse_ns,solve_ns"));
+            };
+            days.insert(
+                day as i32,
+                (Duration::from_nanos(parse), Duration::from_nanos(solve)),
+            );
+        }
+        Ok(Baseline { days })
+    }
+}
+
+/// Renders the results as a table, flagging days more than `threshold` slower
+/// than the baseline. Returns the table and whether anything regressed.
+pub fn render_bench(
+    benches: &[DayBench],
+    baseline: Option<&Baseline>,
+    threshold: f64,
+) -> (String, bool) {
+    let mut regressed = false;
+    let rows: Vec<Vec<String>> = benches
+        .iter()
+        .map(|bench| {
+            let Ok((parse, solve)) = &bench.result else {
+                let mut row = vec![bench.day.to_string(), "ERROR".to_owned()];
+                row.resize(8, String::new());
+                return row;
+            };
+            let change = match baseline.map(|b| b.change(bench.day, parse, solve)) {
+                None => String::new(),
+                Some(None) => "-".to_owned(),
+                Some(Some(change)) if change > threshold => {
+                    regressed = true;
+                    format!("{:+.1}% REGRESSION", change * 100.0)
+                }
+                Some(Some(change)) => format!("{:+.1}%", change * 100.0),
+            };
+            vec![
+                bench.day.to_string(),
+                format!("{:.2?}", parse.mean),
+                format!("{:.2?}", parse.median),
+                format!("{:.2?}", parse.stddev),
+                format!("{:.2?}", solve.mean),
+                format!("{:.2?}", solve.median),
+                format!("{:.2?}", solve.stddev),
+                change,
+            ]
+        })
+        .collect();
+
+    let mut table = layout_table(
+        &[
+            "Day",
+            "Parse mean",
+            "median",
+            "stddev",
+            "Solve mean",
+            "median",
+            "stddev",
+            "vs baseline",
+        ],
+        &rows,
+        None,
+        &[true, true, true, true, true, true, true, false],
+    );
+    for bench in benches {
+        if let Err(e) = &bench.result {
+            table.push_str(&format!("Day {}: {}\n", bench.day, e));
+        }
+    }
+    (table, regressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::new(&[ms(4), ms(2), ms(8), ms(6)]);
+        assert_eq!(stats.mean, ms(5));
+        assert_eq!(stats.median, ms(5));
+        assert_eq!(stats.stddev.as_micros(), 2236);
+
+        assert_eq!(Stats::new(&[ms(3), ms(1), ms(2)]).median, ms(2));
+    }
+
+    #[test]
+    fn test_baseline() {
+        let baseline: Baseline = "day,parse_ns,solve_ns\n1,1000,3000\n".parse().unwrap();
+        let stats = |mean| Stats {
+            mean,
+            median: mean,
+            stddev: Duration::ZERO,
+        };
+        let parse = stats(Duration::from_nanos(1000));
+        let solve = stats(Duration::from_nanos(5000));
+        assert_eq!(baseline.change(1, &parse, &solve), Some(0.5));
+        assert_eq!(baseline.change(2, &parse, &solve), None);
+
+        let mut recorded = Baseline::default();
+        recorded.record(&[DayBench {
+            day: 1,
+            result: Ok((
+                stats(Duration::from_nanos(1000)),
+                stats(Duration::from_nanos(3000)),
+            )),
+        }]);
+        assert_eq!(recorded, baseline);
+
+        assert_eq!(
+            "day,parse_ns,solve_ns\n1,2".parse::<Baseline>(),
+            Err(Error::parse("1,2", "expected day,parse_ns,solve_ns").at_line(2))
+        );
+    }
+}
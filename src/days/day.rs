@@ -1,9 +1,251 @@
+use std::{
+    fmt,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use super::error::Error;
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct DayResult {
     pub part1: String,
     pub part2: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl FromStr for Part {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(Error::invalid_config(format!(
+                "invalid part '{}', expected 1 or 2",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// An example from the puzzle text, with the parameters it needs and its known answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    pub input: &'static str,
+    pub params: &'static [(&'static str, &'static str)],
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+}
+
+impl Example {
+    /// The only part worth solving, when the example has an answer for just one.
+    pub fn part(&self) -> Option<Part> {
+        match (self.part1, self.part2) {
+            (Some(_), None) => Some(Part::One),
+            (None, Some(_)) => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+/// How a day module makes itself known to the [registry](super::registry).
+pub struct Registration {
+    pub year: i32,
+    pub day: i32,
+    pub title: &'static str,
+    pub new: fn() -> Box<dyn Program>,
+}
+
+/// An update on how far a solver has got.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Progress {
+    Step { done: usize, total: usize },
+    Status(String),
+}
+
+/// Receives the progress of the days being run, e.g. to draw a progress line.
+pub trait Reporter: Send + Sync {
+    fn report(&self, year: i32, day: i32, progress: Progress);
+
+    /// Called by the runner once a day has been solved.
+    fn finish(&self, _year: i32, _day: i32) {}
+}
+
+/// Passed to every solver so that long running parts can be stopped by the runner
+/// and can tell it how far they have got.
+#[derive(Clone, Default)]
+pub struct Context {
+    deadline: Option<(Instant, Duration)>,
+    reporter: Option<(Arc<dyn Reporter>, i32, i32)>,
+}
+
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Context")
+            .field("deadline", &self.deadline)
+            .field("reporting", &self.reporter.is_some())
+            .finish()
+    }
+}
+
+impl Context {
+    pub fn with_timeout(limit: Duration) -> Self {
+        Context {
+            deadline: Some((Instant::now() + limit, limit)),
+            reporter: None,
+        }
+    }
+
+    /// Sends the progress of the given day to `reporter`.
+    pub fn reporting_to(self, reporter: Arc<dyn Reporter>, year: i32, day: i32) -> Self {
+        Context {
+            reporter: Some((reporter, year, day)),
+            ..self
+        }
+    }
+
+    /// Reports that `done` of `total` steps are complete.
+    pub fn progress(&self, done: usize, total: usize) {
+        if let Some((reporter, year, day)) = &self.reporter {
+            reporter.report(*year, *day, Progress::Step { done, total });
+        }
+    }
+
+    /// Reports what the solver is busy with. The message is only formatted when
+    /// someone is listening.
+    pub fn status(&self, message: impl fmt::Display) {
+        if let Some((reporter, year, day)) = &self.reporter {
+            reporter.report(*year, *day, Progress::Status(message.to_string()));
+        }
+    }
+
+    /// Fails with [`Error::Timeout`] once the time limit has passed. Solvers that
+    /// loop for a long time should call this regularly and give up when it fails.
+    pub fn check(&self) -> Result<(), Error> {
+        match self.deadline {
+            Some((deadline, limit)) if Instant::now() >= deadline => Err(Error::Timeout(limit)),
+            _ => Ok(()),
+        }
+    }
+}
+
 pub trait Day {
-    fn run(&self, input: String) -> Result<DayResult, String>;
+    type Parsed;
+
+    const EXAMPLES: &'static [Example] = &[];
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error>;
+
+    fn part1(&self, parsed: &Self::Parsed, ctx: &Context) -> Result<String, Error>;
+
+    fn part2(&self, parsed: &Self::Parsed, ctx: &Context) -> Result<Option<String>, Error>;
+
+    /// Parses `input` and solves both parts, without a timeout.
+    fn run(&self, input: String) -> Result<DayResult, Error> {
+        let parsed = self.parse(&input)?;
+        let ctx = Context::default();
+        let part1 = self.part1(&parsed, &ctx)?;
+        let part2 = self.part2(&parsed, &ctx)?;
+        Ok(DayResult { part1, part2 })
+    }
+
+    /// Names of the puzzle parameters that can be overridden with [`Day::set_param`].
+    fn params(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn set_param(&mut self, name: &str, _value: &str) -> Result<(), Error> {
+        Err(unknown_param(name, self.params()))
+    }
+}
+
+pub fn unknown_param(name: &str, params: &[&str]) -> Error {
+    if params.is_empty() {
+        Error::invalid_config(format!("unknown parameter '{}', this day takes none", name))
+    } else {
+        Error::invalid_config(format!(
+            "unknown parameter '{}', expected one of: {}",
+            name,
+            params.join(", ")
+        ))
+    }
+}
+
+/// Object safe view of a [`Day`], used by the runner to hold every day in one map.
+/// Days are shared across threads when several of them run at once.
+pub trait Program: Send + Sync {
+    fn prepare<'a>(&'a self, input: &str) -> Result<Box<dyn Solver + 'a>, Error>;
+
+    fn examples(&self) -> &'static [Example];
+
+    fn param_names(&self) -> &'static [&'static str];
+
+    fn configure(&mut self, name: &str, value: &str) -> Result<(), Error>;
+}
+
+/// A day with its input already parsed, ready to solve either part.
+pub trait Solver {
+    fn part1(&self, ctx: &Context) -> Result<String, Error>;
+
+    fn part2(&self, ctx: &Context) -> Result<Option<String>, Error>;
+}
+
+struct Prepared<'a, D: Day> {
+    day: &'a D,
+    parsed: D::Parsed,
+}
+
+impl dyn Program {
+    /// Like [`Day::run`], for a day that's only known as a `Program`.
+    pub fn run(&self, input: String) -> Result<DayResult, Error> {
+        let solver = self.prepare(&input)?;
+        let ctx = Context::default();
+        let part1 = solver.part1(&ctx)?;
+        let part2 = solver.part2(&ctx)?;
+        Ok(DayResult { part1, part2 })
+    }
+}
+
+impl<D: Day + Send + Sync> Program for D {
+    fn prepare<'a>(&'a self, input: &str) -> Result<Box<dyn Solver + 'a>, Error> {
+        let parsed = self.parse(input)?;
+        Ok(Box::new(Prepared { day: self, parsed }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        D::EXAMPLES
+    }
+
+    fn param_names(&self) -> &'static [&'static str] {
+        self.params()
+    }
+
+    fn configure(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        self.set_param(name, value)
+    }
+}
+
+impl<D: Day> Solver for Prepared<'_, D> {
+    fn part1(&self, ctx: &Context) -> Result<String, Error> {
+        self.day.part1(&self.parsed, ctx)
+    }
+
+    fn part2(&self, ctx: &Context) -> Result<Option<String>, Error> {
+        self.day.part2(&self.parsed, ctx)
+    }
 }
@@ -1,51 +1,56 @@
 use std::str::FromStr;
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::parse::{complete, lines, pair, terminated, uint},
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 1,
+    title: "Historian Hysteria",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let lists: Lists = input.parse()?;
-        let part1 = lists.total_distance().to_string();
+    type Parsed = Lists;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("11"),
+        part2: Some("31"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Lists, Error> {
+        input.parse()
+    }
+
+    fn part1(&self, lists: &Lists, _ctx: &Context) -> Result<String, Error> {
+        Ok(lists.total_distance().to_string())
+    }
 
-        let part2 = Some(lists.similarity().to_string());
-        Ok(DayResult { part1, part2 })
+    fn part2(&self, lists: &Lists, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(lists.similarity().to_string()))
     }
 }
 
 #[derive(Debug)]
-struct Lists {
+pub struct Lists {
     left: Vec<usize>,
     right: Vec<usize>,
 }
 
 impl FromStr for Lists {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut left = Vec::new();
-        let mut right = Vec::new();
-
-        for line in s.lines() {
-            let mut parts = line.split("   ");
-            left.push(
-                parts
-                    .next()
-                    .unwrap()
-                    .parse()
-                    .map_err(|e| format!("failed to parse left in '{}': {}", line, e))?,
-            );
-
-            right.push(
-                parts
-                    .next()
-                    .unwrap()
-                    .parse()
-                    .map_err(|e| format!("failed to parse right in '{}': {}", line, e))?,
-            );
-        }
-
+        let pairs: Vec<(usize, usize)> =
+            complete(s, lines(pair(terminated(uint(), "   "), uint())))?;
+        let (left, right) = pairs.into_iter().unzip();
         Ok(Lists { left, right })
     }
 }
@@ -59,8 +64,8 @@ impl Lists {
         right.sort();
 
         left.into_iter()
-            .zip(right.into_iter())
-            .map(|(l, r)| if l < r { r - l } else { l - r })
+            .zip(right)
+            .map(|(l, r)| r.abs_diff(l))
             .sum()
     }
 
@@ -71,3 +76,28 @@ impl Lists {
             .sum()
     }
 }
+
+const EXAMPLE: &str = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let result = Instance.run(EXAMPLE.to_owned());
+        assert_eq!(
+            result,
+            Ok(DayResult {
+                part1: "11".to_owned(),
+                part2: Some("31".to_owned())
+            })
+        );
+    }
+}
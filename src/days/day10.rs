@@ -1,61 +1,80 @@
-use std::{
-    collections::{HashMap, HashSet},
-    hash::Hash,
-    str::FromStr,
+use std::{collections::HashSet, str::FromStr};
+
+use super::{
+    day::*,
+    error::Error,
+    util::grid::{Coord, Grid},
 };
 
-use super::day::*;
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 10,
+    title: "Hoof It",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let topology: Topology = input.parse()?;
-        let (part1, part2) = topology.trailheads();
-        let part1 = part1.to_string();
-        let part2 = Some(part2.to_string());
-        Ok(DayResult { part1, part2 })
+    type Parsed = Trailheads;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("36"),
+        part2: Some("81"),
+    }];
+
+    /// Both parts come from the same walk over the map, so it's done here and
+    /// solving only one part takes as long as solving both.
+    fn parse(&self, input: &str) -> Result<Trailheads, Error> {
+        Ok(input.parse::<Topology>()?.trailheads())
+    }
+
+    fn part1(&self, trailheads: &Trailheads, _ctx: &Context) -> Result<String, Error> {
+        Ok(trailheads.score.to_string())
+    }
+
+    fn part2(&self, trailheads: &Trailheads, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(trailheads.rating.to_string()))
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Coord(i32, i32);
+/// The total score and rating of the trailheads.
+#[derive(Debug)]
+pub struct Trailheads {
+    score: usize,
+    rating: usize,
+}
+
 #[derive(Debug)]
 struct Topology {
-    heights: HashMap<Coord, u32>,
+    heights: Grid<Option<u32>>,
 }
 
 impl FromStr for Topology {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let heights = s
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .filter(|(_, c)| *c != '.')
-                    .map(move |(x, h)| {
-                        h.to_digit(10)
-                            .ok_or(format!("bad height digit: {}", h))
-                            .map(|h| (Coord(x as i32, y as i32), h))
-                    })
-            })
-            .collect::<Result<_, _>>()?;
+        let heights = Grid::parse_with(s, |_, c| match c {
+            '.' => Some(None),
+            c => c.to_digit(10).map(Some),
+        })?;
         Ok(Topology { heights })
     }
 }
 
 impl Topology {
-    fn trailheads(&self) -> (usize, usize) {
+    fn trailheads(&self) -> Trailheads {
         let (a, b): (Vec<_>, Vec<_>) = self
             .heights
-            .iter()
-            .filter(|(_, h)| **h == 0)
-            .map(|(coord, _)| self.trailheads_from(*coord))
+            .positions(|h| *h == Some(0))
+            .map(|coord| self.trailheads_from(coord))
             .unzip();
-        (a.iter().sum(), b.iter().sum())
+        Trailheads {
+            score: a.iter().sum(),
+            rating: b.iter().sum(),
+        }
     }
 
     fn trailheads_from(&self, coord: Coord) -> (usize, usize) {
@@ -64,43 +83,41 @@ impl Topology {
         let mut rating = 0;
 
         while let Some(coord) = candidates.pop() {
-            let height = self.heights[&coord];
+            let Some(height) = self.heights[coord] else {
+                continue;
+            };
             if height == 9 {
                 ends.insert(coord);
                 rating += 1;
                 continue;
             }
-            let mut new_candidates = vec![];
-            for (dx, dy) in &[(0, 1), (1, 0), (0, -1), (-1, 0)] {
-                let new_coord = Coord(coord.0 + dx, coord.1 + dy);
-                if let Some(new_height) = self.heights.get(&new_coord) {
-                    if *new_height == height + 1 {
-                        new_candidates.push(new_coord);
-                    }
-                }
-            }
-            candidates.extend(new_candidates);
+            candidates.extend(
+                self.heights
+                    .neighbours(coord)
+                    .filter(|&next| self.heights[next] == Some(height + 1)),
+            );
         }
 
         (ends.len(), rating)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_example() {
-        let input = "89010123
+const EXAMPLE: &str = "89010123
 78121874
 87430965
 96549874
 45678903
 32019012
 01329801
-10456732"
-            .to_owned();
+10456732";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = EXAMPLE.to_owned();
         let day = Instance;
         let result = day.run(input);
         assert_eq!(
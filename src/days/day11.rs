@@ -2,22 +2,69 @@ use std::{collections::HashMap, str::FromStr};
 
 use itertools::Itertools;
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::{
+        parse::{complete, map, separated, spaces, uint},
+        parse_param,
+    },
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 11,
+    title: "Plutonian Pebbles",
+    new: || Box::new(Instance::default()),
+};
+
+pub struct Instance {
+    part1_blinks: usize,
+    part2_blinks: usize,
+}
 
-pub struct Instance;
+impl Default for Instance {
+    fn default() -> Self {
+        Self {
+            part1_blinks: 25,
+            part2_blinks: 75,
+        }
+    }
+}
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let mut stones: Stones = input.parse()?;
-        for _ in 0..25 {
-            stones.transform();
-        }
-        let part1 = stones.len().to_string();
-        for _ in 0..50 {
-            stones.transform();
+    type Parsed = Stones;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("55312"),
+        part2: Some("65601038650482"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Stones, Error> {
+        input.parse()
+    }
+
+    fn part1(&self, stones: &Stones, _ctx: &Context) -> Result<String, Error> {
+        Ok(stones.blink(self.part1_blinks).len().to_string())
+    }
+
+    fn part2(&self, stones: &Stones, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(stones.blink(self.part2_blinks).len().to_string()))
+    }
+
+    fn params(&self) -> &'static [&'static str] {
+        &["part1_blinks", "part2_blinks"]
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        match name {
+            "part1_blinks" => self.part1_blinks = parse_param(name, value)?,
+            "part2_blinks" => self.part2_blinks = parse_param(name, value)?,
+            _ => return Err(unknown_param(name, self.params())),
         }
-        let part2 = Some(stones.len().to_string());
-        Ok(DayResult { part1, part2 })
+        Ok(())
     }
 }
 
@@ -29,7 +76,7 @@ impl Stone {
         let num = self.0;
         if num == 0 {
             vec![Stone(1)]
-        } else if num.to_string().len() % 2 == 0 {
+        } else if num.to_string().len().is_multiple_of(2) {
             let s = num.to_string();
             let half = s.len() / 2;
             let left = s[..half].parse().unwrap();
@@ -42,20 +89,16 @@ impl Stone {
     }
 }
 
-#[derive(Debug)]
-struct Stones {
+#[derive(Debug, Clone)]
+pub struct Stones {
     stones: HashMap<Stone, usize>,
 }
 
 impl FromStr for Stones {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let stones: Vec<Stone> = s
-            .split_whitespace()
-            .map(|s| s.parse().map(Stone))
-            .collect::<Result<_, _>>()
-            .map_err(|e| format!("failed to parse stones '{}': {}", s, e))?;
+        let stones = complete(s, separated(map(uint(), Stone), spaces()))?;
 
         let stones = stones
             .into_iter()
@@ -79,19 +122,29 @@ impl Stones {
         self.stones = new_stones;
     }
 
+    fn blink(&self, times: usize) -> Stones {
+        let mut stones = self.clone();
+        for _ in 0..times {
+            stones.transform();
+        }
+        stones
+    }
+
     fn len(&self) -> usize {
         self.stones.values().sum()
     }
 }
 
+const EXAMPLE: &str = "125 17";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test() {
-        let input = "125 17".to_owned();
-        let day = Instance;
+        let input = EXAMPLE.to_owned();
+        let day = Instance::default();
         let result = day.run(input).unwrap();
         assert_eq!(result.part1, "55312");
         assert_eq!(result.part2, Some("65601038650482".to_owned()));
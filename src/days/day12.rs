@@ -1,102 +1,75 @@
-use std::{
-    char,
-    collections::{HashMap, HashSet},
-    str::FromStr,
+use std::{collections::HashSet, str::FromStr};
+
+use super::{
+    day::*,
+    error::Error,
+    util::grid::{Coord, Direction, Grid},
 };
 
-use super::day::*;
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 12,
+    title: "Garden Groups",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let garden: Garden = input.parse()?;
-        let part1 = garden.fencing_price(false).to_string();
-        let part2 = Some(garden.fencing_price(true).to_string());
-        Ok(DayResult { part1, part2 })
+    type Parsed = Garden;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("1930"),
+        part2: Some("1206"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Garden, Error> {
+        input.parse()
+    }
+
+    fn part1(&self, garden: &Garden, _ctx: &Context) -> Result<String, Error> {
+        Ok(garden.fencing_price(false).to_string())
     }
-}
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone)]
-struct Coord {
-    x: i32,
-    y: i32,
+    fn part2(&self, garden: &Garden, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(garden.fencing_price(true).to_string()))
+    }
 }
 
 #[derive(Debug)]
-struct Garden {
-    plants: HashMap<Coord, char>,
+pub struct Garden {
+    plants: Grid<char>,
 }
 
 impl FromStr for Garden {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut plants = HashMap::new();
-        for (y, line) in s.lines().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                plants.insert(
-                    Coord {
-                        x: x as i32,
-                        y: y as i32,
-                    },
-                    c,
-                );
-            }
-        }
-        Ok(Garden { plants })
+        Ok(Garden { plants: s.parse()? })
     }
 }
 
-fn price(next: Coord, remaining: &mut HashMap<Coord, char>, bulk_discount: bool) -> usize {
-    let mut stack = vec![next.clone()];
-    let plant = remaining.get(&next).unwrap().clone();
+/// Prices the region containing `start`, marking its plots as fenced.
+fn price(plants: &Grid<char>, start: Coord, fenced: &mut Grid<bool>, bulk_discount: bool) -> usize {
+    let mut stack = vec![start];
+    let plant = plants[start];
     let mut area = 0;
+    fenced[start] = true;
 
-    let mut visited = HashSet::new();
-    visited.insert(next.clone());
-
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    enum EdgeDirection {
-        HorizontalUp,
-        HorizontalDown,
-        VerticalLeft,
-        VerticalRight,
-    }
-
-    let mut edges = Vec::new();
+    // Each fence is identified by the plot outside it and the direction it faces.
+    let mut edges = HashSet::new();
 
     while let Some(next) = stack.pop() {
-        remaining.remove(&next);
         area += 1;
-        for &(x, y, d) in [
-            (-1, 0, EdgeDirection::VerticalLeft),
-            (1, 0, EdgeDirection::VerticalRight),
-            (0, -1, EdgeDirection::HorizontalDown),
-            (0, 1, EdgeDirection::HorizontalUp),
-        ]
-        .iter()
-        {
-            if x == 0 && y == 0 {
-                continue;
-            }
-            let coord = Coord {
-                x: next.x + x,
-                y: next.y + y,
-            };
-            if visited.contains(&coord) {
-                continue;
-            }
-
-            if let Some(c) = remaining.get(&coord) {
-                if *c == plant {
-                    visited.insert(coord.clone());
-                    stack.push(coord.clone());
-                } else {
-                    edges.push((coord.clone(), d));
-                }
-            } else {
-                edges.push((coord.clone(), d));
+        for d in Direction::ALL {
+            let coord = next.step(d);
+            if plants.get(coord) != Some(&plant) {
+                edges.insert((coord, d));
+            } else if !fenced[coord] {
+                fenced[coord] = true;
+                stack.push(coord);
             }
         }
     }
@@ -105,62 +78,33 @@ fn price(next: Coord, remaining: &mut HashMap<Coord, char>, bulk_discount: bool)
         return edges.len() * area;
     }
 
-    let mut edge_count = 0;
-    while let Some((next, d)) = edges.pop() {
-        edge_count += 1;
-        let moves = if d == EdgeDirection::HorizontalUp || d == EdgeDirection::HorizontalDown {
-            [(-1, 0), (1, 0)]
-        } else {
-            [(0, -1), (0, 1)]
-        };
-        for m in moves {
-            let mut coord = Coord {
-                x: next.x + m.0,
-                y: next.y + m.1,
-            };
-            while let Some((i, _)) = edges
-                .iter()
-                .enumerate()
-                .find(|(_, (c, dir))| c == &coord && &d == dir)
-            {
-                edges.remove(i);
-                coord = Coord {
-                    x: coord.x + m.0,
-                    y: coord.y + m.1,
-                };
-            }
-        }
-    }
-
-    if edge_count % 2 != 0 {
-        dbg!(edge_count, next, plant);
-    }
+    // A side is counted once, at the fence with no neighbour along it to one end.
+    let sides = edges
+        .iter()
+        .filter(|&&(coord, d)| !edges.contains(&(coord.step(d.clockwise()), d)))
+        .count();
 
-    edge_count * area
+    sides * area
 }
 
 impl Garden {
     fn fencing_price(&self, bulk_discount: bool) -> usize {
-        let mut remaining = self.plants.clone();
-        let mut prices = Vec::new();
-
-        while !remaining.is_empty() {
-            let next = remaining.keys().next().unwrap();
-            let price = price(next.clone(), &mut remaining, bulk_discount);
-            prices.push(price);
-        }
-
-        prices.iter().sum()
+        let plants = &self.plants;
+        let mut fenced = Grid::new(plants.width(), plants.height(), false);
+        plants
+            .coords()
+            .map(|coord| {
+                if fenced[coord] {
+                    0
+                } else {
+                    price(plants, coord, &mut fenced, bulk_discount)
+                }
+            })
+            .sum()
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test() {
-        let input = "RRRRIICCFF
+const EXAMPLE: &str = "RRRRIICCFF
 RRRRIICCCF
 VVRRRCCFFF
 VVRCCCJFFF
@@ -169,8 +113,15 @@ VVIVCCJJEE
 VVIIICJJEE
 MIIIIIJJEE
 MIIISIJEEE
-MMMISSJEEE"
-            .to_owned();
+MMMISSJEEE";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let input = EXAMPLE.to_owned();
         let day = Instance;
         let result = day.run(input);
         assert_eq!(
@@ -189,7 +140,11 @@ ABABA
 ABBBA
 AAAAA"
             .to_owned();
-        let mut garden: Garden = input.parse().unwrap();
-        assert_eq!(price(Coord { x: 1, y: 1 }, &mut garden.plants, true), 40);
+        let garden: Garden = input.parse().unwrap();
+        let mut fenced = Grid::new(5, 4, false);
+        assert_eq!(
+            price(&garden.plants, Coord::new(1, 1), &mut fenced, true),
+            40
+        );
     }
 }
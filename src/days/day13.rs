@@ -1,111 +1,83 @@
 use std::str::FromStr;
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::{
+        number::solve_linear,
+        parse::{complete, field, int, pair, preceded, terminated},
+        parse_blocks,
+    },
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 13,
+    title: "Claw Contraption",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let mut claw_machines = input
-            .split("\n\n")
-            .map(|s| s.parse::<ClawMachine>())
-            .collect::<Result<Vec<_>, _>>()?;
+    type Parsed = Vec<ClawMachine>;
 
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("480"),
+        part2: Some("875318608908"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Vec<ClawMachine>, Error> {
+        parse_blocks(input)
+    }
+
+    fn part1(&self, claw_machines: &Vec<ClawMachine>, _ctx: &Context) -> Result<String, Error> {
         let part1 = claw_machines
             .iter()
             .filter_map(|c| c.min_tokens())
             .sum::<usize>()
             .to_string();
+        Ok(part1)
+    }
 
+    fn part2(
+        &self,
+        claw_machines: &Vec<ClawMachine>,
+        _ctx: &Context,
+    ) -> Result<Option<String>, Error> {
         let part2 = claw_machines
-            .iter_mut()
+            .iter()
             .filter_map(|c| {
+                let mut c = c.clone();
                 c.recalibrate();
                 c.min_tokens()
             })
             .sum::<usize>()
             .to_string();
-
-        Ok(DayResult {
-            part1,
-            part2: Some(part2),
-        })
+        Ok(Some(part2))
     }
 }
 
-#[derive(Debug)]
-struct ClawMachine {
+#[derive(Debug, Clone)]
+pub struct ClawMachine {
     button_a: (i64, i64),
     button_b: (i64, i64),
     prize: (i64, i64),
 }
 
-fn parse_button(s: &str) -> Result<(i64, i64), String> {
-    let mut parts = s.split(", ");
-    let x = parts
-        .next()
-        .ok_or("no x")?
-        .split('+')
-        .nth(1)
-        .ok_or("no x")?
-        .parse()
-        .map_err(|e| format!("failed to parse x: {}", e))?;
-    let y = parts
-        .next()
-        .ok_or("no y")?
-        .split('+')
-        .nth(1)
-        .ok_or("no y")?
-        .parse()
-        .map_err(|e| format!("failed to parse y: {}", e))?;
-    Ok((x, y))
-}
-
-fn parse_prize(s: &str) -> Result<(i64, i64), String> {
-    let mut parts = s.split(", ");
-    let x = parts
-        .next()
-        .ok_or("no x")?
-        .split('=')
-        .nth(1)
-        .ok_or("no x")?
-        .parse()
-        .map_err(|e| format!("failed to parse x: {}", e))?;
-    let y = parts
-        .next()
-        .ok_or("no y")?
-        .split('=')
-        .nth(1)
-        .ok_or("no y")?
-        .parse()
-        .map_err(|e| format!("failed to parse y: {}", e))?;
-    Ok((x, y))
-}
 impl ClawMachine {
+    /// Buttons that push the claw along the same line (a singular system) are
+    /// treated as never reaching the prize.
     fn min_tokens(&self) -> Option<usize> {
         let (ax, ay) = self.button_a;
         let (bx, by) = self.button_b;
         let (px, py) = self.prize;
 
-        let ax_py = ax * py;
-        let ay_px = ay * px;
-        let ax_by = ax * by;
-        let ay_bx = ay * bx;
-
-        let l = ax_py - ay_px;
-        let r = ax_by - ay_bx;
-
-        if l % r == 0 {
-            let b = l / r;
-            let rem = px - b * bx;
-            if rem % ax == 0 {
-                let a = rem / ax;
-                if a >= 0 && b >= 0 {
-                    return Some((a * 3 + b) as usize);
-                }
-            }
-        }
-        None
+        let [a, b] = solve_linear([[ax, bx], [ay, by]], [px, py])?;
+        let (a, b) = (a.to_integer()?, b.to_integer()?);
+        (a >= 0 && b >= 0).then(|| (a * 3 + b) as usize)
     }
 
     fn recalibrate(&mut self) {
@@ -115,16 +87,18 @@ impl ClawMachine {
 }
 
 impl FromStr for ClawMachine {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        let button_a = parse_button(lines.next().ok_or("missing button_a")?)
-            .map_err(|e| format!("failed to parse button_a: {}\ninput:\n{}", e, s))?;
-        let button_b = parse_button(lines.next().ok_or("missing button_b")?)
-            .map_err(|e| format!("failed to parse button_b: {}\ninput:\n{}", e, s))?;
-        let prize = parse_prize(lines.next().ok_or("missing prize")?)
-            .map_err(|e| format!("failed to parse prize: {}\ninput:\n{}", e, s))?;
+        let vector = |x, y| pair(preceded(x, int()), preceded(y, int()));
+        let button = |name| terminated(field(name, vector("X+", ", Y+")), "\n");
+        let ((button_a, button_b), prize) = complete(
+            s,
+            pair(
+                pair(button("Button A"), button("Button B")),
+                field("Prize", vector("X=", ", Y=")),
+            ),
+        )?;
         Ok(ClawMachine {
             button_a,
             button_b,
@@ -133,13 +107,7 @@ impl FromStr for ClawMachine {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test() {
-        let input = "Button A: X+94, Y+34
+const EXAMPLE: &str = "Button A: X+94, Y+34
 Button B: X+22, Y+67
 Prize: X=8400, Y=5400
 
@@ -153,8 +121,16 @@ Prize: X=7870, Y=6450
 
 Button A: X+69, Y+23
 Button B: X+27, Y+71
-Prize: X=18641, Y=10279"
-            .to_owned();
+Prize: X=18641, Y=10279";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::error::ParseError;
+
+    #[test]
+    fn test() {
+        let input = EXAMPLE.to_owned();
         let day = Instance;
         let result = day.run(input);
         assert_eq!(
@@ -165,4 +141,20 @@ Prize: X=18641, Y=10279"
             })
         );
     }
+
+    #[test]
+    fn bad_button() {
+        let input = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+x1
+Prize: X=12748, Y=12176";
+        let result = Instance.parse(input);
+        assert!(matches!(
+            result,
+            Err(Error::Parse(ParseError { line: Some(6), .. }))
+        ));
+    }
 }
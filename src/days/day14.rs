@@ -1,6 +1,21 @@
 use std::str::FromStr;
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::{
+        number::crt,
+        parse::{complete, int, pair, preceded, terminated},
+        parse_lines, parse_size_param,
+    },
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 14,
+    title: "Restroom Redoubt",
+    new: || Box::new(Instance::default()),
+};
 
 pub struct Instance {
     width: usize,
@@ -17,83 +32,103 @@ impl Default for Instance {
 }
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
+    type Parsed = Robots;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[("width", "11"), ("height", "7")],
+        part1: Some("12"),
+        part2: Some("105"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Robots, Error> {
         let mut robots: Robots = input.parse()?;
         robots.initialise(self);
-        for _ in 0..100 {
-            robots.step();
+        Ok(robots)
+    }
+
+    fn part1(&self, robots: &Robots, _ctx: &Context) -> Result<String, Error> {
+        Ok(robots.after(100).safety_factor().to_string())
+    }
+
+    /// The first step after part 1's 100 where no two robots overlap. Each
+    /// robot's column only depends on the step modulo the width, and its row on
+    /// the step modulo the height, so this checks every pair of those phases and
+    /// combines the ones without overlaps into a step.
+    fn part2(&self, robots: &Robots, ctx: &Context) -> Result<Option<String>, Error> {
+        let columns: Vec<_> = (0..robots.width).map(|t| robots.columns_after(t)).collect();
+        let rows: Vec<_> = (0..robots.height).map(|t| robots.rows_after(t)).collect();
+
+        let mut first: Option<i64> = None;
+        for (x_phase, columns) in columns.iter().enumerate() {
+            ctx.check()?;
+            for (y_phase, rows) in rows.iter().enumerate() {
+                if robots.overlaps(columns, rows) {
+                    continue;
+                }
+                let phases = [
+                    (x_phase as i64, robots.width as i64),
+                    (y_phase as i64, robots.height as i64),
+                ];
+                // Phases that disagree never line up when the sizes share a factor.
+                let Some((step, period)) = crt(phases) else {
+                    continue;
+                };
+                let step = 101 + (step - 101).rem_euclid(period);
+                first = Some(first.map_or(step, |first| first.min(step)));
+            }
         }
 
-        let part1 = robots.safety_factor().to_string();
-        let mut part2 = None;
+        Ok(first.map(|step| step.to_string()))
+    }
 
-        for _ in 0..10000 {
-            robots.step();
+    fn params(&self) -> &'static [&'static str] {
+        &["width", "height"]
+    }
 
-            if robots.overlap_count() == 0 {
-                robots.print();
-                println!();
-                part2 = Some(robots.total_steps.to_string());
-                break;
-            }
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        match name {
+            "width" => self.width = parse_size_param(name, value)?,
+            "height" => self.height = parse_size_param(name, value)?,
+            _ => return Err(unknown_param(name, self.params())),
         }
-        Ok(DayResult { part1, part2 })
+        Ok(())
     }
 }
 
-#[derive(Debug)]
-struct Robots {
+#[derive(Debug, Clone)]
+pub struct Robots {
     robots: Vec<Robot>,
     width: usize,
     height: usize,
-    total_steps: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Robot {
     position: (i32, i32),
     velocity: (i32, i32),
 }
 
-fn parse_vector(s: &str) -> Result<(i32, i32), String> {
-    let mut parts = s[2..].split(',');
-    let x = parts
-        .next()
-        .ok_or("no x")?
-        .parse()
-        .map_err(|e| format!("failed to parse x: {}", e))?;
-    let y = parts
-        .next()
-        .ok_or("no y")?
-        .parse()
-        .map_err(|e| format!("failed to parse y: {}", e))?;
-    Ok((x, y))
-}
-
 impl FromStr for Robot {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split_whitespace();
-        let position = parse_vector(parts.next().ok_or("no position")?)?;
-        let velocity = parse_vector(parts.next().ok_or("no velocity")?)?;
+        let vector = || pair(terminated(int(), ","), int());
+        let (position, velocity) =
+            complete(s, pair(preceded("p=", vector()), preceded(" v=", vector())))?;
         Ok(Robot { position, velocity })
     }
 }
 
 impl FromStr for Robots {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let robots = s
-            .lines()
-            .map(|s| s.parse())
-            .collect::<Result<Vec<_>, _>>()?;
+        let robots = parse_lines(s)?;
         Ok(Robots {
             robots,
             width: 0,
             height: 0,
-            total_steps: 0,
         })
     }
 }
@@ -104,32 +139,42 @@ impl Robots {
         self.height = instance.height;
     }
 
-    fn step(&mut self) {
-        for robot in &mut self.robots {
-            robot.position.0 += robot.velocity.0;
-            robot.position.1 += robot.velocity.1;
+    fn after(&self, steps: usize) -> Robots {
+        let robots = self
+            .robots
+            .iter()
+            .map(|robot| Robot {
+                position: (
+                    wrap(robot.position.0, robot.velocity.0, steps, self.width),
+                    wrap(robot.position.1, robot.velocity.1, steps, self.height),
+                ),
+                velocity: robot.velocity,
+            })
+            .collect();
+        Robots { robots, ..*self }
+    }
 
-            while robot.position.0 < 0 {
-                robot.position.0 += self.width as i32;
-            }
-            while robot.position.0 >= self.width as i32 {
-                robot.position.0 -= self.width as i32;
-            }
-            while robot.position.1 < 0 {
-                robot.position.1 += self.height as i32;
-            }
-            while robot.position.1 >= self.height as i32 {
-                robot.position.1 -= self.height as i32;
-            }
-        }
-        self.total_steps += 1;
+    /// Every robot's column after `steps`, which repeats every `width` steps.
+    fn columns_after(&self, steps: usize) -> Vec<i32> {
+        self.robots
+            .iter()
+            .map(|robot| wrap(robot.position.0, robot.velocity.0, steps, self.width))
+            .collect()
+    }
+
+    /// Every robot's row after `steps`, which repeats every `height` steps.
+    fn rows_after(&self, steps: usize) -> Vec<i32> {
+        self.robots
+            .iter()
+            .map(|robot| wrap(robot.position.1, robot.velocity.1, steps, self.height))
+            .collect()
     }
 
     fn safety_factor(&self) -> usize {
         let x_size = self.width as i32 / 2;
         let y_size = self.height as i32 / 2;
 
-        let mut counts = vec![0; 4];
+        let mut counts = [0; 4];
 
         for robot in &self.robots {
             if robot.position.0 < x_size && robot.position.1 < y_size {
@@ -146,45 +191,20 @@ impl Robots {
         counts.iter().product()
     }
 
-    fn overlap_count(&self) -> usize {
-        let mut grid = vec![vec![0; self.width]; self.height];
-        for robot in &self.robots {
-            grid[robot.position.1 as usize][robot.position.0 as usize] += 1;
-        }
-
-        grid.iter()
-            .map(|row| row.iter().filter(|&&i| i > 1).count())
-            .sum()
-    }
-
-    fn print(&self) {
-        let mut grid = vec![vec![0; self.width]; self.height];
-        for robot in &self.robots {
-            grid[robot.position.1 as usize][robot.position.0 as usize] += 1;
-        }
-
-        for row in grid {
-            println!(
-                "{}",
-                row.iter()
-                    .map(|i| if *i == 0 {
-                        ".".to_owned()
-                    } else {
-                        i.to_string()
-                    })
-                    .collect::<String>()
-            );
-        }
+    fn overlaps(&self, columns: &[i32], rows: &[i32]) -> bool {
+        let mut occupied = vec![false; self.width * self.height];
+        columns.iter().zip(rows).any(|(&x, &y)| {
+            let cell = &mut occupied[y as usize * self.width + x as usize];
+            std::mem::replace(cell, true)
+        })
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn wrap(position: i32, velocity: i32, steps: usize, size: usize) -> i32 {
+    (position as i64 + velocity as i64 * steps as i64).rem_euclid(size as i64) as i32
+}
 
-    #[test]
-    fn test() {
-        let input = "p=0,4 v=3,-3
+const EXAMPLE: &str = "p=0,4 v=3,-3
 p=6,3 v=-1,-3
 p=10,3 v=-1,2
 p=2,0 v=2,-1
@@ -196,8 +216,15 @@ p=9,3 v=2,3
 p=7,3 v=-1,2
 p=2,4 v=2,-3
 p=9,5 v=-3,-3
-"
-        .to_owned();
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let input = EXAMPLE.to_owned();
         let day = Instance {
             width: 11,
             height: 7,
@@ -211,4 +238,15 @@ p=9,5 v=-3,-3
             })
         );
     }
+
+    #[test]
+    fn test_sizes_are_positive() {
+        let mut day = Instance::default();
+        assert_eq!(
+            day.set_param("width", "0"),
+            Err(Error::invalid_config("width must be positive"))
+        );
+        assert!(day.set_param("height", "-7").is_err());
+        assert_eq!(day.set_param("height", "7"), Ok(()));
+    }
 }
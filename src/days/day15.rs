@@ -1,34 +1,81 @@
 use std::{collections::HashSet, str::FromStr};
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::{
+        grid::{Coord, Direction, Grid},
+        parse::{any_char, complete, lines, many1, try_map},
+    },
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 15,
+    title: "Warehouse Woes",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
+    type Parsed = (Warehouse, Instructions);
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE_1,
+            params: &[],
+            part1: Some("2028"),
+            part2: Some("1751"),
+        },
+        Example {
+            input: EXAMPLE_2,
+            params: &[],
+            part1: Some("10092"),
+            part2: Some("9021"),
+        },
+        Example {
+            input: EXAMPLE_3,
+            params: &[],
+            part1: Some("908"),
+            part2: Some("618"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<(Warehouse, Instructions), Error> {
         let (w, i) = input
             .split_once("\n\n")
-            .ok_or(format!("bad input: {}", input))?;
-        let mut warehouse: Warehouse = w.parse()?;
-        let mut wide_warehouse = WideWarehouse::from_warehouse(&warehouse);
-        let instuctions: Instructions = i.parse()?;
-        warehouse.apply_instructions(&instuctions);
-        wide_warehouse.apply_instructions(&instuctions);
-
-        let part1 = warehouse.gps().to_string();
-        let part2 = Some(wide_warehouse.gps().to_string());
-        Ok(DayResult { part1, part2 })
+            .ok_or_else(|| Error::parse("", "missing blank line before instructions"))?;
+        let instructions_line = w.lines().count() + 2;
+        Ok((
+            w.parse()?,
+            i.parse().map_err(|e: Error| e.at_line(instructions_line))?,
+        ))
+    }
+
+    fn part1(
+        &self,
+        (warehouse, instructions): &(Warehouse, Instructions),
+        _ctx: &Context,
+    ) -> Result<String, Error> {
+        let mut warehouse = warehouse.clone();
+        warehouse.apply_instructions(instructions);
+        Ok(warehouse.gps().to_string())
     }
-}
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone)]
-struct Coord {
-    x: i32,
-    y: i32,
+    fn part2(
+        &self,
+        (warehouse, instructions): &(Warehouse, Instructions),
+        _ctx: &Context,
+    ) -> Result<Option<String>, Error> {
+        let mut wide_warehouse = WideWarehouse::from_warehouse(warehouse);
+        wide_warehouse.apply_instructions(instructions);
+        Ok(Some(wide_warehouse.gps().to_string()))
+    }
 }
 
-#[derive(Debug)]
-struct Warehouse {
+#[derive(Debug, Clone)]
+pub struct Warehouse {
     boxes: HashSet<Coord>,
     walls: HashSet<Coord>,
     robot: Coord,
@@ -37,115 +84,46 @@ struct Warehouse {
 }
 
 impl FromStr for Warehouse {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut boxes = HashSet::new();
-        let mut walls = HashSet::new();
-        let mut robot = None;
-        let mut width = 0;
-        let mut height = 0;
-        for (y, line) in s.lines().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                match c {
-                    'O' => {
-                        boxes.insert(Coord {
-                            x: x as i32,
-                            y: y as i32,
-                        });
-                    }
-                    '#' => {
-                        walls.insert(Coord {
-                            x: x as i32,
-                            y: y as i32,
-                        });
-                    }
-                    '@' => {
-                        robot = Some(Coord {
-                            x: x as i32,
-                            y: y as i32,
-                        });
-                    }
-                    '.' => {}
-                    _ => return Err(format!("unexpected character '{}'", c)),
-                }
-                width = x;
-            }
-            height = y;
-        }
+        let grid = Grid::parse_with(s, |_, c| matches!(c, 'O' | '#' | '@' | '.').then_some(c))?;
+        let mut robot = grid.positions(|&c| c == '@');
         Ok(Warehouse {
-            boxes,
-            walls,
-            robot: robot.ok_or("no robot")?,
-            width: width + 1,
-            height: height + 1,
+            boxes: grid.positions(|&c| c == 'O').collect(),
+            walls: grid.positions(|&c| c == '#').collect(),
+            robot: robot.next().ok_or_else(|| Error::parse("", "no robot"))?,
+            width: grid.width(),
+            height: grid.height(),
         })
     }
 }
 
 #[derive(Debug)]
-struct Instructions {
-    instructions: Vec<Instruction>,
-}
-
-#[derive(Debug)]
-enum Instruction {
-    Up,
-    Down,
-    Left,
-    Right,
+pub struct Instructions {
+    instructions: Vec<Direction>,
 }
 
 impl FromStr for Instructions {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let instructions = s
-            .chars()
-            .filter(|c| *c != '\n')
-            .map(|c| match c {
-                '^' => Ok(Instruction::Up),
-                'v' => Ok(Instruction::Down),
-                '<' => Ok(Instruction::Left),
-                '>' => Ok(Instruction::Right),
-                _ => Err(format!("unexpected character '{}'", c)),
-            })
-            .collect::<Result<_, _>>()?;
+        let arrow = try_map(any_char(), |c| {
+            Direction::from_arrow(c).ok_or_else(|| format!("unexpected character '{}'", c))
+        });
+        let instructions = complete(s, lines(many1(arrow)))?.concat();
         Ok(Instructions { instructions })
     }
 }
 
-impl Coord {
-    fn apply(&self, i: &Instruction) -> Coord {
-        match i {
-            Instruction::Up => Coord {
-                x: self.x,
-                y: self.y - 1,
-            },
-            Instruction::Down => Coord {
-                x: self.x,
-                y: self.y + 1,
-            },
-            Instruction::Left => Coord {
-                x: self.x - 1,
-                y: self.y,
-            },
-            Instruction::Right => Coord {
-                x: self.x + 1,
-                y: self.y,
-            },
-        }
-    }
-}
-
 impl Warehouse {
     fn apply_instructions(&mut self, instructions: &Instructions) {
         for i in &instructions.instructions {
-            let robot = self.robot.apply(i);
+            let robot = self.robot.step(*i);
             if self.boxes.contains(&robot) {
-                let mut box_ = robot.clone();
+                let mut box_ = robot;
                 while self.boxes.contains(&box_) {
-                    box_ = box_.apply(i);
+                    box_ = box_.step(*i);
                 }
                 if !self.walls.contains(&box_) {
                     self.boxes.remove(&robot);
@@ -171,17 +149,11 @@ impl Warehouse {
     fn print(&self) {
         for y in 0..self.height {
             for x in 0..self.width {
-                let c = if self.robot.x == x as i32 && self.robot.y == y as i32 {
+                let c = if self.robot == Coord::new(x as i32, y as i32) {
                     '@'
-                } else if self.boxes.contains(&Coord {
-                    x: x as i32,
-                    y: y as i32,
-                }) {
+                } else if self.boxes.contains(&Coord::new(x as i32, y as i32)) {
                     'O'
-                } else if self.walls.contains(&Coord {
-                    x: x as i32,
-                    y: y as i32,
-                }) {
+                } else if self.walls.contains(&Coord::new(x as i32, y as i32)) {
                     '#'
                 } else {
                     '.'
@@ -206,25 +178,14 @@ impl WideWarehouse {
         let boxes = warehouse
             .boxes
             .iter()
-            .map(|b| Coord { x: b.x * 2, y: b.y })
+            .map(|b| Coord::new(b.x * 2, b.y))
             .collect();
         let walls = warehouse
             .walls
             .iter()
-            .flat_map(|w| {
-                vec![
-                    Coord { x: w.x * 2, y: w.y },
-                    Coord {
-                        x: w.x * 2 + 1,
-                        y: w.y,
-                    },
-                ]
-            })
+            .flat_map(|w| vec![Coord::new(w.x * 2, w.y), Coord::new(w.x * 2 + 1, w.y)])
             .collect();
-        let robot = Coord {
-            x: warehouse.robot.x * 2,
-            y: warehouse.robot.y,
-        };
+        let robot = Coord::new(warehouse.robot.x * 2, warehouse.robot.y);
         WideWarehouse {
             boxes,
             walls,
@@ -236,25 +197,22 @@ impl WideWarehouse {
 
     fn apply_instructions(&mut self, instructions: &Instructions) {
         for i in &instructions.instructions {
-            let robot = self.robot.apply(i);
+            let robot = self.robot.step(*i);
             if let Some(hit) = self.hits_box(&robot) {
                 let original_state = self.boxes.clone();
 
-                let mut to_resolve = vec![hit.apply(i)];
+                let mut to_resolve = vec![hit.step(*i)];
                 self.boxes.remove(&hit);
                 let mut has_collision = false;
                 while let Some(box_) = to_resolve.pop() {
                     if let Some(hit) = self.hits_box(&box_) {
                         self.boxes.remove(&hit);
-                        to_resolve.push(hit.apply(i));
+                        to_resolve.push(hit.step(*i));
                     }
-                    let right_side = Coord {
-                        x: box_.x + 1,
-                        y: box_.y,
-                    };
+                    let right_side = box_.step(Direction::East);
                     if let Some(hit) = self.hits_box(&right_side) {
                         self.boxes.remove(&hit);
-                        to_resolve.push(hit.apply(i));
+                        to_resolve.push(hit.step(*i));
                     }
                     if self.walls.contains(&box_) || self.walls.contains(&right_side) {
                         self.boxes = original_state;
@@ -278,7 +236,7 @@ impl WideWarehouse {
         self.boxes
             .iter()
             .find(|coord| coord.y == c.y && (coord.x == c.x || coord.x == c.x - 1))
-            .cloned()
+            .copied()
     }
 
     fn gps(&self) -> usize {
@@ -293,21 +251,15 @@ impl WideWarehouse {
         let mut wide_box = false;
         for y in 0..self.height {
             for x in 0..self.width {
-                let c = if self.robot.x == x as i32 && self.robot.y == y as i32 {
+                let c = if self.robot == Coord::new(x as i32, y as i32) {
                     '@'
                 } else if wide_box {
                     wide_box = false;
                     ']'
-                } else if self.boxes.contains(&Coord {
-                    x: x as i32,
-                    y: y as i32,
-                }) {
+                } else if self.boxes.contains(&Coord::new(x as i32, y as i32)) {
                     wide_box = true;
                     '['
-                } else if self.walls.contains(&Coord {
-                    x: x as i32,
-                    y: y as i32,
-                }) {
+                } else if self.walls.contains(&Coord::new(x as i32, y as i32)) {
                     '#'
                 } else {
                     '.'
@@ -319,13 +271,7 @@ impl WideWarehouse {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn small_example() {
-        let input = "########
+const EXAMPLE_1: &str = "########
 #..O.O.#
 ##@.O..#
 #...O..#
@@ -335,22 +281,9 @@ mod tests {
 ########
 
 <^^>>>vv<v>>v<<
-"
-        .to_owned();
-        let day = Instance;
-        let result = day.run(input);
-        assert_eq!(
-            result,
-            Ok(DayResult {
-                part1: "2028".to_owned(),
-                part2: Some("1751".to_owned())
-            })
-        );
-    }
+";
 
-    #[test]
-    fn test() {
-        let input = "##########
+const EXAMPLE_2: &str = "##########
 #..O..O.O#
 #......O.#
 #.OO..O.O#
@@ -371,8 +304,41 @@ vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
 <><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
 ^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
 v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
-"
-        .to_owned();
+";
+
+const EXAMPLE_3: &str = "#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::error::ParseError;
+
+    #[test]
+    fn small_example() {
+        let input = EXAMPLE_1.to_owned();
+        let day = Instance;
+        let result = day.run(input);
+        assert_eq!(
+            result,
+            Ok(DayResult {
+                part1: "2028".to_owned(),
+                part2: Some("1751".to_owned())
+            })
+        );
+    }
+
+    #[test]
+    fn test() {
+        let input = EXAMPLE_2.to_owned();
         let day = Instance;
         let result = day.run(input);
         assert_eq!(
@@ -386,17 +352,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
 
     #[test]
     fn wide_exampe() {
-        let input = "#######
-#...#.#
-#.....#
-#..OO@#
-#..O..#
-#.....#
-#######
-
-<vv<<^^<<^^
-"
-        .to_owned();
+        let input = EXAMPLE_3.to_owned();
         let day = Instance;
         let result = day.run(input);
         assert_eq!(
@@ -407,4 +363,27 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
             })
         );
     }
+
+    #[test]
+    fn unexpected_characters() {
+        let result = Instance.parse("#####\n#.@x#\n#####\n\n<>\n");
+        assert!(matches!(
+            result,
+            Err(Error::Parse(ParseError {
+                line: Some(2),
+                column: Some(4),
+                ..
+            }))
+        ));
+
+        let result = Instance.parse("#####\n#.@.#\n#####\n\n<>\n<?\n");
+        assert!(matches!(
+            result,
+            Err(Error::Parse(ParseError {
+                line: Some(6),
+                column: Some(2),
+                ..
+            }))
+        ));
+    }
 }
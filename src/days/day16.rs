@@ -1,189 +1,116 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    str::FromStr,
+use std::{collections::HashSet, str::FromStr};
+
+use super::{
+    day::*,
+    error::Error,
+    util::{
+        grid::{Coord, Direction, Grid},
+        search::dijkstra,
+    },
 };
 
-use super::day::*;
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 16,
+    title: "Reindeer Maze",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let maze: Maze = input.parse()?;
-        let (part1, part2) = maze.lowest_score();
-        let part1 = part1.to_string();
-        let part2 = Some(part2.to_string());
-        Ok(DayResult { part1, part2 })
+    type Parsed = BestPaths;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("7036"),
+        part2: Some("45"),
+    }];
+
+    /// The search for part 1 finds part 2's paths too, so it's done here.
+    fn parse(&self, input: &str) -> Result<BestPaths, Error> {
+        input
+            .parse::<Maze>()?
+            .best_paths()
+            .ok_or_else(|| Error::unsolvable("no path to the end"))
     }
-}
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone)]
-struct Coord {
-    x: usize,
-    y: usize,
-}
+    fn part1(&self, paths: &BestPaths, _ctx: &Context) -> Result<String, Error> {
+        Ok(paths.score.to_string())
+    }
 
-impl Coord {
-    fn forward(&self, orientation: Orientation) -> Coord {
-        match orientation {
-            Orientation::North => Coord {
-                x: self.x,
-                y: self.y - 1,
-            },
-            Orientation::East => Coord {
-                x: self.x + 1,
-                y: self.y,
-            },
-            Orientation::South => Coord {
-                x: self.x,
-                y: self.y + 1,
-            },
-            Orientation::West => Coord {
-                x: self.x - 1,
-                y: self.y,
-            },
-        }
+    fn part2(&self, paths: &BestPaths, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(paths.tiles.to_string()))
     }
 }
 
+/// The lowest score of any path to the end, along with the number of tiles on
+/// any of the paths with that score.
+#[derive(Debug)]
+pub struct BestPaths {
+    score: usize,
+    tiles: usize,
+}
+
 #[derive(Debug)]
 struct Maze {
     start: Coord,
     end: Coord,
-    walls: HashSet<Coord>,
+    walls: Grid<bool>,
 }
 
 impl FromStr for Maze {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut start = None;
         let mut end = None;
-        let mut walls = HashSet::new();
-
-        for (y, line) in s.lines().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                let coord = Coord { x, y };
-                match c {
-                    'S' => {
-                        start = Some(coord);
-                    }
-                    'E' => {
-                        end = Some(coord);
-                    }
-                    '#' => {
-                        walls.insert(coord);
-                    }
-                    _ => {}
-                }
+        let walls = Grid::parse_with(s, |coord, c| {
+            match c {
+                'S' => start = Some(coord),
+                'E' => end = Some(coord),
+                _ => {}
             }
-        }
+            Some(c == '#')
+        })?;
 
         Ok(Maze {
-            start: start.ok_or("no start")?,
-            end: end.ok_or("no end")?,
+            start: start.ok_or_else(|| Error::parse("", "no start"))?,
+            end: end.ok_or_else(|| Error::parse("", "no end"))?,
             walls,
         })
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Orientation {
-    North,
-    East,
-    South,
-    West,
-}
-
-impl Orientation {
-    fn clockwise(&self) -> Self {
-        match self {
-            Orientation::North => Orientation::East,
-            Orientation::East => Orientation::South,
-            Orientation::South => Orientation::West,
-            Orientation::West => Orientation::North,
-        }
-    }
-
-    fn anticlockwise(&self) -> Self {
-        match self {
-            Orientation::North => Orientation::West,
-            Orientation::East => Orientation::North,
-            Orientation::South => Orientation::East,
-            Orientation::West => Orientation::South,
-        }
-    }
-}
-
 impl Maze {
-    fn lowest_score(&self) -> (usize, usize) {
-        let mut seen_states: HashMap<(Coord, Orientation), usize> = HashMap::new();
-
-        let mut open_states = VecDeque::new();
-        open_states.push_back((
-            self.start.clone(),
-            Orientation::East,
-            0,
-            vec![self.start.clone()],
-        ));
-
-        let mut min_score = std::usize::MAX;
-        let mut on_best_path = HashSet::new();
-        while let Some((coord, orientation, score, visited)) = open_states.pop_front() {
-            if coord == self.end {
-                if min_score > score {
-                    min_score = score;
-                    on_best_path = HashSet::from_iter(visited);
-                } else if min_score == score {
-                    on_best_path.extend(visited);
-                }
-                continue;
-            }
-
-            if score > min_score {
-                continue;
-            }
-
-            if let Some(&prev_score) = seen_states.get(&(coord.clone(), orientation)) {
-                if prev_score < score {
-                    continue;
-                }
-            }
-
-            seen_states.insert((coord.clone(), orientation), score);
-
-            let forward = coord.forward(orientation);
-            if !self.walls.contains(&forward) {
-                let mut new_visited = visited.clone();
-                new_visited.push(forward.clone());
-                open_states.push_front((forward, orientation, score + 1, new_visited));
-            }
-
-            open_states.push_back((
-                coord.clone(),
-                orientation.clockwise(),
-                score + 1000,
-                visited.clone(),
-            ));
-            open_states.push_back((
-                coord.clone(),
-                orientation.anticlockwise(),
-                score + 1000,
-                visited.clone(),
-            ));
-        }
-
-        (min_score, on_best_path.len())
+    fn best_paths(&self) -> Option<BestPaths> {
+        let search = dijkstra(
+            (self.start, Direction::East),
+            |&(coord, facing)| {
+                let forward = coord.step(facing);
+                let open = self.walls.get(forward) == Some(&false);
+                open.then_some(((forward, facing), 1)).into_iter().chain([
+                    ((coord, facing.clockwise()), 1000),
+                    ((coord, facing.anticlockwise()), 1000),
+                ])
+            },
+            |&(coord, _)| coord == self.end,
+        );
+        let score = search.goal_cost()?;
+        let tiles: HashSet<Coord> = search
+            .on_shortest_paths()
+            .into_iter()
+            .map(|(coord, _)| coord)
+            .collect();
+        Some(BestPaths {
+            score,
+            tiles: tiles.len(),
+        })
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test() {
-        let input = "###############
+const EXAMPLE: &str = "###############
 #.......#....E#
 #.#.###.#.###.#
 #.....#.#...#.#
@@ -197,8 +124,15 @@ mod tests {
 #.....#...#.#.#
 #.###.#.#.#.#.#
 #S..#.....#...#
-###############"
-            .to_owned();
+###############";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let input = EXAMPLE.to_owned();
         let day = Instance;
         let result = day.run(input);
         assert_eq!(
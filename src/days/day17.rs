@@ -1,27 +1,57 @@
 use std::str::FromStr;
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::parse::{
+        complete, field, key_value, lines, pair, preceded, separated, terminated, uint, word,
+    },
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 17,
+    title: "Chronospatial Computer",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let mut computer: Computer = input.parse()?;
-        let output = computer.run();
+    type Parsed = Computer;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("5,7,3,0"),
+        part2: Some("117440"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Computer, Error> {
+        input.parse()
+    }
+
+    fn part1(&self, computer: &Computer, _ctx: &Context) -> Result<String, Error> {
+        let output = computer.clone().run();
 
         let part1 = output
             .iter()
             .map(|i| i.to_string())
             .collect::<Vec<_>>()
             .join(",");
-        let part2 = Some(find_output(computer.program).to_string());
-        Ok(DayResult { part1, part2 })
+        Ok(part1)
+    }
+
+    fn part2(&self, computer: &Computer, ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(
+            find_output(computer.program.clone(), ctx)?.to_string(),
+        ))
     }
 }
 
 const SEARCHES: usize = 16;
 
-fn find_output(program: Vec<usize>) -> usize {
+fn find_output(program: Vec<usize>, ctx: &Context) -> Result<usize, Error> {
     let mut computer = Computer {
         registers: [0, 0, 0],
         program,
@@ -31,13 +61,15 @@ fn find_output(program: Vec<usize>) -> usize {
     let mut next = 1;
     let mut search_start = 0;
     let mut target = computer.program.len();
+    ctx.status("finding the range of register values");
     loop {
+        ctx.check()?;
         let output = run_computer(&mut computer, next);
         if output.len() == target {
             if last == next - 1 {
                 if search_start == 0 {
                     search_start = next;
-                    target = target + 1;
+                    target += 1;
                 } else {
                     break;
                 }
@@ -51,14 +83,17 @@ fn find_output(program: Vec<usize>) -> usize {
             }
         } else {
             last = next;
-            next = next << 1;
+            next <<= 1;
         }
     }
 
     let search_end = last;
     let mut next_ranges = vec![(search_start, search_end)];
 
-    for index in (0..computer.program.len()).rev() {
+    let digits = computer.program.len();
+    for index in (0..digits).rev() {
+        ctx.check()?;
+        ctx.progress(digits - 1 - index, digits);
         let mut new_next_ranges = Vec::new();
         for next_range in next_ranges {
             let (search_start, search_end) = next_range;
@@ -72,7 +107,7 @@ fn find_output(program: Vec<usize>) -> usize {
                 let target = computer.program[index];
                 if output[index] == target {
                     if index == 0 {
-                        return start;
+                        return Ok(start);
                     }
                     new_next_ranges.push(find_range(
                         &mut computer,
@@ -102,7 +137,9 @@ fn find_output(program: Vec<usize>) -> usize {
 
         next_ranges = deduped;
     }
-    panic!("no solution found");
+    Err(Error::unsolvable(
+        "no register value reproduces the program",
+    ))
 }
 
 fn find_range(
@@ -187,8 +224,8 @@ impl Combo {
     }
 }
 
-#[derive(Debug)]
-struct Computer {
+#[derive(Debug, Clone)]
+pub struct Computer {
     registers: [usize; 3],
     program: Vec<usize>,
 }
@@ -210,37 +247,16 @@ impl Instruction {
 }
 
 impl FromStr for Computer {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        let mut registers = [0; 3];
-
-        for i in 0..3 {
-            let line = lines.next().ok_or("missing register")?;
-            let parts = line.split_whitespace().collect::<Vec<_>>();
-            if parts.len() != 3 {
-                return Err(format!("invalid register line '{}'", line));
-            }
-
-            registers[i] = parts[2]
-                .parse()
-                .map_err(|e| format!("failed to parse register '{}': {}", parts[1], e))?;
-        }
-
-        lines.next().ok_or("missing program")?;
-
-        let program = lines
-            .next()
-            .ok_or("missing program")?
-            .strip_prefix("Program: ")
-            .ok_or("missing program prefix")?
-            .split(",")
-            .map(|i| {
-                i.parse::<usize>()
-                    .map_err(|e| format!("failed to parse code '{}': {}", i, e))
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let registers = lines(key_value(preceded("Register ", word()), uint()));
+        let program = field("Program", separated(uint(), ","));
+        let (registers, program) = complete(s, pair(terminated(registers, "\n\n"), program))?;
+        let registers = match registers[..] {
+            [("A", a), ("B", b), ("C", c)] => [a, b, c],
+            _ => return Err(Error::parse("", "expected registers A, B and C")),
+        };
 
         Ok(Self { registers, program })
     }
@@ -302,19 +318,20 @@ impl Computer {
     }
 }
 
+const EXAMPLE: &str = "Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0
+";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test() {
-        let input = "Register A: 2024
-Register B: 0
-Register C: 0
-
-Program: 0,3,5,4,3,0
-"
-        .to_owned();
+        let input = EXAMPLE.to_owned();
         let day = Instance;
         let result = day.run(input).unwrap();
         assert_eq!(result.part1, "5,7,3,0");
@@ -1,9 +1,19 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    str::FromStr,
+use super::{
+    day::*,
+    error::Error,
+    util::{
+        grid::{Coord, Grid},
+        parse_lines, parse_param, parse_size_param,
+        search::bfs,
+    },
 };
 
-use super::day::*;
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 18,
+    title: "RAM Run",
+    new: || Box::new(Instance::default()),
+};
 
 pub struct Instance {
     grid_size: usize,
@@ -20,122 +30,99 @@ impl Default for Instance {
 }
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let mut memory = Memory::new(self.grid_size);
-        let coords: Vec<Coord> = input
-            .lines()
-            .map(|line| line.parse())
-            .collect::<Result<_, _>>()?;
+    type Parsed = Vec<Coord>;
 
-        coords
-            .iter()
-            .take(self.falling_bytes)
-            .for_each(|coord| memory.corrupt(coord.clone()));
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[("grid_size", "6"), ("falling_bytes", "12")],
+        part1: Some("22"),
+        part2: Some("6,1"),
+    }];
 
-        let part1 = memory.shortest_path().ok_or("no path found")?.to_string();
+    fn parse(&self, input: &str) -> Result<Vec<Coord>, Error> {
+        parse_lines(input)
+    }
 
-        let mut part2 = None;
+    fn part1(&self, coords: &Vec<Coord>, _ctx: &Context) -> Result<String, Error> {
+        let memory = self.corrupted_memory(coords);
+        let steps = memory
+            .shortest_path()
+            .ok_or_else(|| Error::unsolvable("no path found"))?;
+        Ok(steps.to_string())
+    }
+
+    fn part2(&self, coords: &Vec<Coord>, ctx: &Context) -> Result<Option<String>, Error> {
+        let mut memory = self.corrupted_memory(coords);
 
-        for coord in coords.iter().skip(self.falling_bytes) {
-            memory.corrupt(coord.clone());
+        let total = coords.len().saturating_sub(self.falling_bytes);
+        for (i, coord) in coords.iter().skip(self.falling_bytes).enumerate() {
+            ctx.check()?;
+            ctx.progress(i, total);
+            memory.corrupt(*coord);
             if memory.shortest_path().is_none() {
-                part2 = Some(format!("{},{}", coord.x, coord.y));
-                break;
+                return Ok(Some(coord.to_string()));
             }
         }
-        Ok(DayResult { part1, part2 })
+        Ok(None)
+    }
+
+    fn params(&self) -> &'static [&'static str] {
+        &["grid_size", "falling_bytes"]
     }
-}
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone)]
-struct Coord {
-    x: isize,
-    y: isize,
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        match name {
+            "grid_size" => self.grid_size = parse_size_param(name, value)?,
+            "falling_bytes" => self.falling_bytes = parse_param(name, value)?,
+            _ => return Err(unknown_param(name, self.params())),
+        }
+        Ok(())
+    }
 }
 
-impl FromStr for Coord {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(',');
-        let x = parts
-            .next()
-            .ok_or("missing x")?
-            .parse::<isize>()
-            .map_err(|e| e.to_string())?;
-        let y = parts
-            .next()
-            .ok_or("missing y")?
-            .parse::<isize>()
-            .map_err(|e| e.to_string())?;
-        Ok(Coord { x, y })
+impl Instance {
+    fn corrupted_memory(&self, coords: &[Coord]) -> Memory {
+        let mut memory = Memory::new(self.grid_size);
+        coords
+            .iter()
+            .take(self.falling_bytes)
+            .for_each(|coord| memory.corrupt(*coord));
+        memory
     }
 }
 
 #[derive(Debug)]
 struct Memory {
-    corrupted: HashSet<Coord>,
-    grid_size: usize,
+    corrupted: Grid<bool>,
 }
 
 impl Memory {
     fn new(grid_size: usize) -> Self {
         Self {
-            corrupted: HashSet::new(),
-            grid_size,
+            corrupted: Grid::new(grid_size + 1, grid_size + 1, false),
         }
     }
 
+    /// Marks `coord` as corrupted. Bytes falling outside of memory are ignored.
     fn corrupt(&mut self, coord: Coord) {
-        self.corrupted.insert(coord);
+        if let Some(cell) = self.corrupted.get_mut(coord) {
+            *cell = true;
+        }
     }
 
     fn shortest_path(&self) -> Option<usize> {
-        let target = Coord {
-            x: self.grid_size as isize,
-            y: self.grid_size as isize,
-        };
-
-        let mut states = VecDeque::from_iter([(Coord { x: 0, y: 0 }, 0)]);
-        let mut visited = HashSet::new();
-
-        while let Some((coord, steps)) = states.pop_back() {
-            if coord == target {
-                return Some(steps);
-            }
-            for (x, y) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-                let new_coord = Coord {
-                    x: coord.x + x,
-                    y: coord.y + y,
-                };
-                if new_coord.x < 0 || new_coord.y < 0 {
-                    continue;
-                }
-                if new_coord.x > self.grid_size as isize || new_coord.y > self.grid_size as isize {
-                    continue;
-                }
-                if self.corrupted.contains(&new_coord) {
-                    continue;
-                }
-                if visited.contains(&new_coord) {
-                    continue;
-                }
-                visited.insert(new_coord.clone());
-                states.push_front((new_coord, steps + 1));
-            }
-        }
-
-        None
+        let grid = &self.corrupted;
+        let target = Coord::new(grid.width() as i32 - 1, grid.height() as i32 - 1);
+        bfs(
+            Coord::new(0, 0),
+            |&coord| grid.neighbours(coord).filter(|&next| !grid[next]),
+            |&coord| coord == target,
+        )
+        .goal_cost()
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test() {
-        let input = "5,4
+const EXAMPLE: &str = "5,4
 4,2
 4,5
 3,0
@@ -159,8 +146,15 @@ mod tests {
 1,0
 0,5
 1,6
-2,0"
-        .to_owned();
+2,0";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let input = EXAMPLE.to_owned();
         let day = Instance {
             grid_size: 6,
             falling_bytes: 12,
@@ -174,4 +168,14 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn blocked() {
+        let day = Instance {
+            grid_size: 2,
+            falling_bytes: 3,
+        };
+        let result = day.run("1,0\n1,1\n1,2".to_owned());
+        assert_eq!(result, Err(Error::unsolvable("no path found")));
+    }
 }
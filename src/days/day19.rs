@@ -1,17 +1,46 @@
 use std::{collections::HashMap, slice, str::FromStr};
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::parse::{any_char, complete, lines, many1, pair, separated, terminated, try_map},
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 19,
+    title: "Linen Layout",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let towel_patterns: TowelPatterns = input.parse()?;
+    type Parsed = TowelPatterns;
 
-        let (part1, part2) = towel_patterns.count_valid_and_patterns();
-        let part1 = part1.to_string();
-        let part2 = Some(part2.to_string());
-        Ok(DayResult { part1, part2 })
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("6"),
+        part2: Some("16"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<TowelPatterns, Error> {
+        input.parse()
+    }
+
+    fn part1(&self, towel_patterns: &TowelPatterns, _ctx: &Context) -> Result<String, Error> {
+        Ok(towel_patterns.count_valid_and_patterns().0.to_string())
+    }
+
+    fn part2(
+        &self,
+        towel_patterns: &TowelPatterns,
+        _ctx: &Context,
+    ) -> Result<Option<String>, Error> {
+        Ok(Some(
+            towel_patterns.count_valid_and_patterns().1.to_string(),
+        ))
     }
 }
 
@@ -24,51 +53,39 @@ enum Colour {
     Green,
 }
 
-impl FromStr for Colour {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "w" => Ok(Colour::White),
-            "u" => Ok(Colour::Blue),
-            "b" => Ok(Colour::Black),
-            "r" => Ok(Colour::Red),
-            "g" => Ok(Colour::Green),
-            _ => Err("Invalid colour".to_owned()),
+impl Colour {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'w' => Some(Colour::White),
+            'u' => Some(Colour::Blue),
+            'b' => Some(Colour::Black),
+            'r' => Some(Colour::Red),
+            'g' => Some(Colour::Green),
+            _ => None,
         }
     }
 }
 
 #[derive(Debug)]
-struct TowelPatterns {
+pub struct TowelPatterns {
     patterns: Vec<Vec<Colour>>,
     towels: Vec<Vec<Colour>>,
 }
 
 impl FromStr for TowelPatterns {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        let patterns = lines
-            .next()
-            .ok_or("No patterns")?
-            .to_owned()
-            .split(", ")
-            .map(|line| {
-                line.chars()
-                    .map(|c| c.to_string().parse())
-                    .collect::<Result<Vec<Colour>, String>>()
-            })
-            .collect::<Result<Vec<Vec<Colour>>, String>>()?;
-        lines.next();
-        let towels = lines
-            .map(|line| {
-                line.chars()
-                    .map(|c| c.to_string().parse())
-                    .collect::<Result<Vec<Colour>, String>>()
+        let colour = || {
+            try_map(any_char(), |c| {
+                Colour::from_char(c).ok_or_else(|| format!("invalid colour '{}'", c))
             })
-            .collect::<Result<Vec<Vec<Colour>>, String>>()?;
+        };
+        let patterns = separated(many1(colour()), ", ");
+        let (patterns, towels) = complete(
+            s,
+            pair(terminated(patterns, "\n\n"), lines(many1(colour()))),
+        )?;
         Ok(Self { patterns, towels })
     }
 }
@@ -77,11 +94,16 @@ fn valid_count(towel: &[Colour], patterns: &[Vec<Colour>]) -> usize {
     let mut remainders = vec![(towel, 1)];
     let mut total_count: usize = 0;
 
-    while remainders.len() > 0 {
+    while !remainders.is_empty() {
         let mut new_remainders = HashMap::new();
-        let longest = remainders.iter().map(|(c,_)|c.len()).max().unwrap();
-        new_remainders.extend(remainders.iter().filter(|(c,_)|c.len()<longest).map(|(p, c)|((p.as_ptr(), p.len()), *c)));
-        for (remainder, count) in remainders.into_iter().filter(|(c,_)|c.len()==longest) {
+        let longest = remainders.iter().map(|(c, _)| c.len()).max().unwrap();
+        new_remainders.extend(
+            remainders
+                .iter()
+                .filter(|(c, _)| c.len() < longest)
+                .map(|(p, c)| ((p.as_ptr(), p.len()), *c)),
+        );
+        for (remainder, count) in remainders.into_iter().filter(|(c, _)| c.len() == longest) {
             for pattern in patterns {
                 if remainder.len() < pattern.len() {
                     continue;
@@ -111,9 +133,10 @@ fn valid_count(towel: &[Colour], patterns: &[Vec<Colour>]) -> usize {
         }
         remainders = new_remainders
             .into_iter()
-            .map(|((p, l), c)| 
-            // safe as using same lifetime and valid slices from above
-            unsafe { (slice::from_raw_parts(p, l), c) })
+            .map(|((p, l), c)| {
+                // safe as using same lifetime and valid slices from above
+                unsafe { (slice::from_raw_parts(p, l), c) }
+            })
             .collect();
     }
 
@@ -135,13 +158,7 @@ impl TowelPatterns {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test() {
-        let input = "r, wr, b, g, bwu, rb, gb, br
+const EXAMPLE: &str = "r, wr, b, g, bwu, rb, gb, br
 
 brwrr
 bggr
@@ -150,8 +167,15 @@ rrbgbr
 ubwu
 bwurrg
 brgr
-bbrgwb"
-            .to_owned();
+bbrgwb";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let input = EXAMPLE.to_owned();
         let day = Instance;
         let result = day.run(input);
         assert_eq!(
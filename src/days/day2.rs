@@ -1,45 +1,61 @@
 use std::{cmp::Reverse, str::FromStr};
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::{
+        parse::{complete, separated, uint},
+        parse_lines,
+    },
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 2,
+    title: "Red-Nosed Reports",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let reports = input
-            .lines()
-            .map(|line| line.parse())
-            .collect::<Result<Vec<Report>, _>>()?;
-        let part1 = reports.iter().filter(|r| r.is_safe()).count().to_string();
+    type Parsed = Vec<Report>;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("2"),
+        part2: Some("4"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Vec<Report>, Error> {
+        parse_lines(input)
+    }
+
+    fn part1(&self, reports: &Vec<Report>, _ctx: &Context) -> Result<String, Error> {
+        Ok(reports.iter().filter(|r| r.is_safe()).count().to_string())
+    }
+
+    fn part2(&self, reports: &Vec<Report>, _ctx: &Context) -> Result<Option<String>, Error> {
         let part2 = reports
             .iter()
             .filter(|r| r.is_safe_tolerant())
             .count()
             .to_string();
-        Ok(DayResult {
-            part1,
-            part2: Some(part2),
-        })
+        Ok(Some(part2))
     }
 }
 
 #[derive(Debug)]
-struct Report {
+pub struct Report {
     entries: Vec<usize>,
 }
 
 impl FromStr for Report {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let entries = s
-            .split(" ")
-            .map(|num| {
-                num.parse()
-                    .map_err(|e| format!("failed to parse entry '{}': {}", num, e))
-            })
-            .collect::<Result<_, _>>()?;
-
+        let entries = complete(s, separated(uint(), " "))?;
         Ok(Report { entries })
     }
 }
@@ -51,7 +67,7 @@ impl Report {
                 .iter()
                 .zip(self.entries.iter().skip(1))
                 .all(|(a, b)| {
-                    let diff = if a > b { a - b } else { b - a };
+                    let diff = a.abs_diff(*b);
                     diff > 0 && diff < 4
                 })
         } else {
@@ -68,3 +84,28 @@ impl Report {
             })
     }
 }
+
+const EXAMPLE: &str = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let result = Instance.run(EXAMPLE.to_owned());
+        assert_eq!(
+            result,
+            Ok(DayResult {
+                part1: "2".to_owned(),
+                part2: Some("4".to_owned())
+            })
+        );
+    }
+}
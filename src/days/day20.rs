@@ -1,9 +1,21 @@
-use std::{
-    collections::{HashMap, HashSet},
-    str::FromStr,
+use std::{collections::HashMap, str::FromStr};
+
+use super::{
+    day::*,
+    error::Error,
+    util::{
+        grid::{Coord, Grid},
+        parse_param,
+        search::bfs,
+    },
 };
 
-use super::day::*;
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 20,
+    title: "Race Condition",
+    new: || Box::new(Instance::default()),
+};
 
 pub struct Instance {
     threshold: usize,
@@ -16,116 +28,78 @@ impl Default for Instance {
 }
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let racetrack: Racetrack = input.parse()?;
+    type Parsed = Racetrack;
 
-        let part1 = racetrack.cheats_at_least(self.threshold, 2).to_string();
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[("threshold", "50")],
+        part1: Some("1"),
+        part2: Some("285"),
+    }];
 
-        let part2 = racetrack.cheats_at_least(self.threshold, 20).to_string();
-        let part2 = Some(part2);
-        Ok(DayResult { part1, part2 })
+    fn parse(&self, input: &str) -> Result<Racetrack, Error> {
+        input.parse()
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Coord {
-    x: i32,
-    y: i32,
-}
+    fn part1(&self, racetrack: &Racetrack, _ctx: &Context) -> Result<String, Error> {
+        Ok(racetrack.cheats_at_least(self.threshold, 2).to_string())
+    }
 
-impl Coord {
-    fn shift(&self, direction: Direction) -> Coord {
-        match direction {
-            Direction::North => Coord {
-                x: self.x,
-                y: self.y - 1,
-            },
-            Direction::South => Coord {
-                x: self.x,
-                y: self.y + 1,
-            },
-            Direction::East => Coord {
-                x: self.x + 1,
-                y: self.y,
-            },
-            Direction::West => Coord {
-                x: self.x - 1,
-                y: self.y,
-            },
-        }
+    fn part2(&self, racetrack: &Racetrack, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(
+            racetrack.cheats_at_least(self.threshold, 20).to_string(),
+        ))
     }
 
-    fn distance(&self, other: &Coord) -> u32 {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    fn params(&self) -> &'static [&'static str] {
+        &["threshold"]
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        match name {
+            "threshold" => self.threshold = parse_param(name, value)?,
+            _ => return Err(unknown_param(name, self.params())),
+        }
+        Ok(())
     }
 }
 
 #[derive(Debug)]
-struct Racetrack {
-    walls: HashSet<Coord>,
+pub struct Racetrack {
+    walls: Grid<bool>,
     start: Coord,
     end: Coord,
 }
 
 impl FromStr for Racetrack {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut walls = HashSet::new();
         let mut start = None;
         let mut end = None;
-        for (y, line) in s.lines().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                match c {
-                    '#' => {
-                        walls.insert(Coord {
-                            x: x as i32,
-                            y: y as i32,
-                        });
-                    }
-                    'S' => {
-                        start = Some(Coord {
-                            x: x as i32,
-                            y: y as i32,
-                        });
-                    }
-                    'E' => {
-                        end = Some(Coord {
-                            x: x as i32,
-                            y: y as i32,
-                        });
-                    }
-                    _ => {}
-                }
+        let walls = Grid::parse_with(s, |coord, c| {
+            match c {
+                'S' => start = Some(coord),
+                'E' => end = Some(coord),
+                _ => {}
             }
-        }
+            Some(c == '#')
+        })?;
         Ok(Self {
             walls,
-            start: start.ok_or("No start found")?,
-            end: end.ok_or("No end found")?,
+            start: start.ok_or_else(|| Error::parse("", "No start found"))?,
+            end: end.ok_or_else(|| Error::parse("", "No end found"))?,
         })
     }
 }
 
-fn calculate_distances(walls: &HashSet<Coord>, start: Coord) -> HashMap<Coord, usize> {
-    let mut distances: HashMap<Coord, usize> = HashMap::new();
-
-    let mut positions = vec![(start, 0)];
-
-    while let Some((p, d)) = positions.pop() {
-        if distances.contains_key(&p) || walls.contains(&p) {
-            continue;
-        }
-
-        positions.push((p.shift(Direction::East), d + 1));
-        positions.push((p.shift(Direction::West), d + 1));
-        positions.push((p.shift(Direction::South), d + 1));
-        positions.push((p.shift(Direction::North), d + 1));
-
-        distances.insert(p, d);
-    }
-
-    distances
+fn calculate_distances(walls: &Grid<bool>, start: Coord) -> HashMap<Coord, usize> {
+    bfs(
+        start,
+        |&p| walls.neighbours(p).filter(|&next| !walls[next]),
+        |_| false,
+    )
+    .into_costs()
 }
 
 impl Racetrack {
@@ -136,10 +110,9 @@ impl Racetrack {
         let mut savings = 0;
         for start in start_distances.keys() {
             for end in start_distances.keys() {
-                if start != end && start.distance(end) <= max_length {
-                    let new_total = start_distances[&start]
-                        + end_distances[&end]
-                        + start.distance(end) as usize;
+                let shortcut = start.manhattan(*end);
+                if start != end && shortcut <= max_length {
+                    let new_total = start_distances[start] + end_distances[end] + shortcut as usize;
                     if total_distance > new_total {
                         let saving = total_distance - new_total;
                         if saving >= picosecond_threshold {
@@ -153,21 +126,7 @@ impl Racetrack {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test() {
-        let input = "###############
+const EXAMPLE: &str = "###############
 #...#...#.....#
 #.#.#.#.#.###.#
 #S#...#.#.#...#
@@ -181,8 +140,15 @@ mod tests {
 #.#...#.#.#...#
 #.#.#.#.#.#.###
 #...#...#...###
-###############"
-            .to_owned();
+###############";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let input = EXAMPLE.to_owned();
         assert_eq!(
             Instance { threshold: 50 }.run(input),
             Ok(DayResult {
@@ -1,34 +1,97 @@
-use std::{collections::HashMap, fmt::Debug, str::FromStr};
-
-use super::day::*;
+use std::{collections::HashMap, fmt::Debug};
+
+use super::{
+    day::*,
+    error::Error,
+    util::{
+        grid::{Coord, Direction},
+        parse::{any_char, complete, lines, many1, try_map},
+        parse_param,
+    },
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 21,
+    title: "Keypad Conundrum",
+    new: || Box::new(Instance::default()),
+};
+
+pub struct Instance {
+    part1_robots: usize,
+    part2_robots: usize,
+}
 
-pub struct Instance;
+impl Default for Instance {
+    fn default() -> Self {
+        Self {
+            part1_robots: 2,
+            part2_robots: 25,
+        }
+    }
+}
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let numeric_keys = parse_input(&input)?;
+    type Parsed = Vec<(usize, Vec<NumericKey>)>;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("126384"),
+        part2: Some("154115708116294"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Vec<(usize, Vec<NumericKey>)>, Error> {
+        parse_input(input)
+    }
+
+    fn part1(
+        &self,
+        numeric_keys: &Vec<(usize, Vec<NumericKey>)>,
+        _ctx: &Context,
+    ) -> Result<String, Error> {
+        Ok(complexity(numeric_keys, self.part1_robots).to_string())
+    }
 
-        let part1 = complexity(&numeric_keys, 2).to_string();
-        let part2 = Some(complexity(&numeric_keys, 25).to_string());
-        Ok(DayResult { part1, part2 })
+    fn part2(
+        &self,
+        numeric_keys: &Vec<(usize, Vec<NumericKey>)>,
+        _ctx: &Context,
+    ) -> Result<Option<String>, Error> {
+        Ok(Some(
+            complexity(numeric_keys, self.part2_robots).to_string(),
+        ))
+    }
+
+    fn params(&self) -> &'static [&'static str] {
+        &["part1_robots", "part2_robots"]
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        match name {
+            "part1_robots" => self.part1_robots = parse_param(name, value)?,
+            "part2_robots" => self.part2_robots = parse_param(name, value)?,
+            _ => return Err(unknown_param(name, self.params())),
+        }
+        Ok(())
     }
 }
 
-fn parse_input(input: &str) -> Result<Vec<(usize, Vec<NumericKey>)>, String> {
-    input
-        .lines()
-        .map(|line| {
-            line.trim_end_matches("A")
-                .parse::<usize>()
-                .map_err(|e| e.to_string())
-                .and_then(|numeric| {
-                    line.chars()
-                        .map(|c| c.to_string().parse::<NumericKey>())
-                        .collect::<Result<Vec<_>, _>>()
-                        .map(|keys| (numeric, keys))
-                })
+fn parse_input(input: &str) -> Result<Vec<(usize, Vec<NumericKey>)>, Error> {
+    let key = try_map(any_char(), |c| {
+        NumericKey::from_char(c).ok_or_else(|| format!("invalid key '{}'", c))
+    });
+    let codes = complete(input, lines(many1(key)))?;
+    Ok(codes
+        .into_iter()
+        .map(|keys| {
+            let numeric = keys
+                .iter()
+                .filter_map(NumericKey::digit)
+                .fold(0, |n, d| n * 10 + d);
+            (numeric, keys)
         })
-        .collect()
+        .collect())
 }
 
 #[derive(Debug)]
@@ -52,8 +115,8 @@ impl CachedComplexity {
         let mut total = 0;
 
         for key in keys {
-            let mut horizontal = current.directions(&key.position(), false);
-            let mut vertical = current.directions(&key.position(), true);
+            let mut horizontal = directions(current, key.position(), false);
+            let mut vertical = directions(current, key.position(), true);
             horizontal.push(DirectionalKey::Activate);
             vertical.push(DirectionalKey::Activate);
 
@@ -100,8 +163,8 @@ impl CachedComplexity {
         if let Some(existing) = self.best_cache.get(&cache_key) {
             existing.clone()
         } else {
-            let mut horizontal = from.directions(&to, false);
-            let mut vertical = from.directions(&to, true);
+            let mut horizontal = directions(from, to, false);
+            let mut vertical = directions(from, to, true);
             horizontal.push(DirectionalKey::Activate);
             vertical.push(DirectionalKey::Activate);
 
@@ -144,6 +207,19 @@ enum DirectionalKey {
     Activate,
 }
 
+impl DirectionalKey {
+    /// Where pressing the key moves the arm, which `Activate` doesn't.
+    fn move_from(&self, coord: Coord) -> Coord {
+        match self {
+            DirectionalKey::Up => coord.step(Direction::North),
+            DirectionalKey::Down => coord.step(Direction::South),
+            DirectionalKey::Left => coord.step(Direction::West),
+            DirectionalKey::Right => coord.step(Direction::East),
+            DirectionalKey::Activate => coord,
+        }
+    }
+}
+
 trait Position {
     fn start() -> Coord;
     fn position(&self) -> Coord;
@@ -153,20 +229,20 @@ trait Position {
 impl Position for DirectionalKey {
     fn position(&self) -> Coord {
         match self {
-            DirectionalKey::Up => Coord { x: 1, y: 0 },
-            DirectionalKey::Activate => Coord { x: 2, y: 0 },
+            DirectionalKey::Up => Coord::new(1, 0),
+            DirectionalKey::Activate => Coord::new(2, 0),
 
-            DirectionalKey::Left => Coord { x: 0, y: 1 },
-            DirectionalKey::Down => Coord { x: 1, y: 1 },
-            DirectionalKey::Right => Coord { x: 2, y: 1 },
+            DirectionalKey::Left => Coord::new(0, 1),
+            DirectionalKey::Down => Coord::new(1, 1),
+            DirectionalKey::Right => Coord::new(2, 1),
         }
     }
 
     fn is_valid(start: &Coord, directions: &[DirectionalKey]) -> bool {
         let mut current = *start;
         for d in directions {
-            current = current.move_direction(*d);
-            if current == (Coord { x: 0, y: 0 }) {
+            current = d.move_from(current);
+            if current == Coord::new(0, 0) {
                 return false;
             }
         }
@@ -179,7 +255,7 @@ impl Position for DirectionalKey {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum NumericKey {
+pub enum NumericKey {
     Zero,
     One,
     Two,
@@ -193,37 +269,31 @@ enum NumericKey {
     Activate,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-struct Coord {
-    x: usize,
-    y: usize,
-}
-
 impl Position for NumericKey {
     fn position(&self) -> Coord {
         match self {
-            NumericKey::Seven => Coord { x: 0, y: 0 },
-            NumericKey::Eight => Coord { x: 1, y: 0 },
-            NumericKey::Nine => Coord { x: 2, y: 0 },
+            NumericKey::Seven => Coord::new(0, 0),
+            NumericKey::Eight => Coord::new(1, 0),
+            NumericKey::Nine => Coord::new(2, 0),
 
-            NumericKey::Four => Coord { x: 0, y: 1 },
-            NumericKey::Five => Coord { x: 1, y: 1 },
-            NumericKey::Six => Coord { x: 2, y: 1 },
+            NumericKey::Four => Coord::new(0, 1),
+            NumericKey::Five => Coord::new(1, 1),
+            NumericKey::Six => Coord::new(2, 1),
 
-            NumericKey::One => Coord { x: 0, y: 2 },
-            NumericKey::Two => Coord { x: 1, y: 2 },
-            NumericKey::Three => Coord { x: 2, y: 2 },
+            NumericKey::One => Coord::new(0, 2),
+            NumericKey::Two => Coord::new(1, 2),
+            NumericKey::Three => Coord::new(2, 2),
 
-            NumericKey::Zero => Coord { x: 1, y: 3 },
-            NumericKey::Activate => Coord { x: 2, y: 3 },
+            NumericKey::Zero => Coord::new(1, 3),
+            NumericKey::Activate => Coord::new(2, 3),
         }
     }
 
     fn is_valid(start: &Coord, directions: &[DirectionalKey]) -> bool {
         let mut current = *start;
         for d in directions {
-            current = current.move_direction(*d);
-            if current == (Coord { x: 0, y: 3 }) {
+            current = d.move_from(current);
+            if current == Coord::new(0, 3) {
                 return false;
             }
         }
@@ -235,88 +305,82 @@ impl Position for NumericKey {
     }
 }
 
-impl Coord {
-    fn directions(&self, other: &Coord, vertical_first: bool) -> Vec<DirectionalKey> {
-        let mut horizontal = vec![];
-        let mut vertical = vec![];
+fn directions(from: Coord, to: Coord, vertical_first: bool) -> Vec<DirectionalKey> {
+    let mut horizontal = vec![];
+    let mut vertical = vec![];
 
-        if self.x < other.x {
-            horizontal.extend(vec![DirectionalKey::Right; other.x - self.x]);
-        } else if self.x > other.x {
-            horizontal.extend(vec![DirectionalKey::Left; self.x - other.x]);
-        }
-        if self.y < other.y {
-            vertical.extend(vec![DirectionalKey::Down; other.y - self.y]);
-        } else if self.y > other.y {
-            vertical.extend(vec![DirectionalKey::Up; self.y - other.y]);
-        }
+    let distance = from.x.abs_diff(to.x) as usize;
+    if from.x < to.x {
+        horizontal.extend(vec![DirectionalKey::Right; distance]);
+    } else if from.x > to.x {
+        horizontal.extend(vec![DirectionalKey::Left; distance]);
+    }
+    let distance = from.y.abs_diff(to.y) as usize;
+    if from.y < to.y {
+        vertical.extend(vec![DirectionalKey::Down; distance]);
+    } else if from.y > to.y {
+        vertical.extend(vec![DirectionalKey::Up; distance]);
+    }
 
-        if vertical_first {
-            vertical.extend(horizontal);
-            vertical
-        } else {
-            horizontal.extend(vertical);
-            horizontal
-        }
+    if vertical_first {
+        vertical.extend(horizontal);
+        vertical
+    } else {
+        horizontal.extend(vertical);
+        horizontal
     }
+}
 
-    fn move_direction(&self, direction: DirectionalKey) -> Coord {
-        match direction {
-            DirectionalKey::Up => Coord {
-                x: self.x,
-                y: self.y - 1,
-            },
-            DirectionalKey::Down => Coord {
-                x: self.x,
-                y: self.y + 1,
-            },
-            DirectionalKey::Left => Coord {
-                x: self.x - 1,
-                y: self.y,
-            },
-            DirectionalKey::Right => Coord {
-                x: self.x + 1,
-                y: self.y,
-            },
-            DirectionalKey::Activate => *self,
+impl NumericKey {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '1' => Some(NumericKey::One),
+            '2' => Some(NumericKey::Two),
+            '3' => Some(NumericKey::Three),
+            '4' => Some(NumericKey::Four),
+            '5' => Some(NumericKey::Five),
+            '6' => Some(NumericKey::Six),
+            '7' => Some(NumericKey::Seven),
+            '8' => Some(NumericKey::Eight),
+            '9' => Some(NumericKey::Nine),
+            '0' => Some(NumericKey::Zero),
+            'A' => Some(NumericKey::Activate),
+            _ => None,
         }
     }
-}
 
-impl FromStr for NumericKey {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "1" => Ok(NumericKey::One),
-            "2" => Ok(NumericKey::Two),
-            "3" => Ok(NumericKey::Three),
-            "4" => Ok(NumericKey::Four),
-            "5" => Ok(NumericKey::Five),
-            "6" => Ok(NumericKey::Six),
-            "7" => Ok(NumericKey::Seven),
-            "8" => Ok(NumericKey::Eight),
-            "9" => Ok(NumericKey::Nine),
-            "0" => Ok(NumericKey::Zero),
-            "A" => Ok(NumericKey::Activate),
-            _ => Err("Invalid key".to_owned()),
+    /// The digit on the key, or `None` for the activate key.
+    fn digit(&self) -> Option<usize> {
+        match self {
+            NumericKey::Zero => Some(0),
+            NumericKey::One => Some(1),
+            NumericKey::Two => Some(2),
+            NumericKey::Three => Some(3),
+            NumericKey::Four => Some(4),
+            NumericKey::Five => Some(5),
+            NumericKey::Six => Some(6),
+            NumericKey::Seven => Some(7),
+            NumericKey::Eight => Some(8),
+            NumericKey::Nine => Some(9),
+            NumericKey::Activate => None,
         }
     }
 }
 
+const EXAMPLE: &str = "029A
+980A
+179A
+456A
+379A";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test() {
-        let input = "029A
-980A
-179A
-456A
-379A"
-            .to_owned();
-        let day = Instance;
+        let input = EXAMPLE.to_owned();
+        let day = Instance::default();
         let result = day.run(input);
         assert_eq!(
             result,
@@ -333,7 +397,7 @@ mod tests {
         let nine = NumericKey::Nine.position();
 
         assert_eq!(
-            one.directions(&nine, false),
+            directions(one, nine, false),
             vec![
                 DirectionalKey::Right,
                 DirectionalKey::Right,
@@ -1,27 +1,72 @@
 use std::collections::HashMap;
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::{
+        parse::{complete, lines, uint},
+        parse_param,
+    },
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 22,
+    title: "Monkey Market",
+    new: || Box::new(Instance::default()),
+};
+
+pub struct Instance {
+    iterations: usize,
+}
 
-pub struct Instance;
+impl Default for Instance {
+    fn default() -> Self {
+        Self { iterations: 2000 }
+    }
+}
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let numbers = parse_input(input.as_str())?;
-        let part1 = part1(numbers.as_slice()).to_string();
-        let part2 = Some(part2(&numbers).to_string());
-        Ok(DayResult { part1, part2 })
+    type Parsed = Vec<usize>;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("37327623"),
+        part2: Some("24"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Vec<usize>, Error> {
+        parse_input(input)
+    }
+
+    fn part1(&self, numbers: &Vec<usize>, _ctx: &Context) -> Result<String, Error> {
+        Ok(part1(numbers, self.iterations).to_string())
+    }
+
+    fn part2(&self, numbers: &Vec<usize>, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(part2(numbers, self.iterations).to_string()))
+    }
+
+    fn params(&self) -> &'static [&'static str] {
+        &["iterations"]
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        match name {
+            "iterations" => self.iterations = parse_param(name, value)?,
+            _ => return Err(unknown_param(name, self.params())),
+        }
+        Ok(())
     }
 }
 
-fn parse_input(input: &str) -> Result<Vec<usize>, String> {
-    input
-        .lines()
-        .map(|line| line.parse::<usize>().map_err(|e| e.to_string()))
-        .collect()
+fn parse_input(input: &str) -> Result<Vec<usize>, Error> {
+    complete(input, lines(uint()))
 }
 
-fn part1(numbers: &[usize]) -> usize {
-    numbers.iter().map(|n| secret_2000(*n)).sum()
+fn part1(numbers: &[usize], iterations: usize) -> usize {
+    numbers.iter().map(|n| nth_secret(*n, iterations)).sum()
 }
 
 fn next_number(secret: usize) -> usize {
@@ -30,9 +75,8 @@ fn next_number(secret: usize) -> usize {
     let secret = mix(secret, secret / 32);
     let secret = prune(secret);
     let secret = mix(secret, secret * 2048);
-    let secret = prune(secret);
 
-    secret
+    prune(secret)
 }
 
 fn mix(secret: usize, number: usize) -> usize {
@@ -43,19 +87,19 @@ fn prune(secret: usize) -> usize {
     secret % 16_777_216
 }
 
-fn secret_2000(initial: usize) -> usize {
+fn nth_secret(initial: usize, n: usize) -> usize {
     let mut secret = initial;
-    for _ in 0..2000 {
+    for _ in 0..n {
         secret = next_number(secret);
     }
     secret
 }
 
-fn part2(numbers: &[usize]) -> usize {
+fn part2(numbers: &[usize], iterations: usize) -> usize {
     let mut runs = HashMap::new();
 
     for number in numbers {
-        let runs_for_number = four_runs_from_initial(*number);
+        let runs_for_number = four_runs_from_initial(*number, iterations);
         for (run, price) in runs_for_number {
             runs.entry(run)
                 .and_modify(|p| *p += price as usize)
@@ -66,11 +110,11 @@ fn part2(numbers: &[usize]) -> usize {
     *runs.values().max().unwrap()
 }
 
-fn prices(number: usize) -> Vec<i8> {
+fn prices(number: usize, iterations: usize) -> Vec<i8> {
     let mut prices = Vec::new();
     let mut secret = number;
 
-    for _ in 0..=2000 {
+    for _ in 0..=iterations {
         prices.push((secret % 10) as i8);
         secret = next_number(secret);
     }
@@ -99,32 +143,31 @@ fn four_runs(prices_and_deltas: &[(i8, i8)]) -> HashMap<(i8, i8, i8, i8), i8> {
 
         let price = prices_and_deltas[i + 3].0;
 
-        if !runs.contains_key(&run) {
-            runs.insert(run, price);
-        }
+        runs.entry(run).or_insert(price);
     }
 
     runs
 }
 
-fn four_runs_from_initial(number: usize) -> HashMap<(i8, i8, i8, i8), i8> {
-    let prices = prices(number);
+fn four_runs_from_initial(number: usize, iterations: usize) -> HashMap<(i8, i8, i8, i8), i8> {
+    let prices = prices(number, iterations);
     let deltas = add_price_deltas(&prices);
     four_runs(&deltas)
 }
 
+const EXAMPLE: &str = "1
+10
+100
+2024";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test() {
-        let input = "1
-10
-100
-2024"
-            .to_owned();
-        let day = Instance;
+        let input = EXAMPLE.to_owned();
+        let day = Instance::default();
         let result = day.run(input);
         assert_eq!(
             result,
@@ -152,12 +195,12 @@ mod tests {
 
     #[test]
     fn test_secret_2000() {
-        assert_eq!(secret_2000(1), 8685429);
+        assert_eq!(nth_secret(1, 2000), 8685429);
     }
 
     #[test]
     fn test_deltas() {
-        let prices = prices(123);
+        let prices = prices(123, 2000);
 
         let deltas = add_price_deltas(&prices);
 
@@ -181,10 +224,10 @@ mod tests {
 
     #[test]
     fn four_runs() {
-        let a = four_runs_from_initial(1);
-        let b = four_runs_from_initial(2);
-        let c = four_runs_from_initial(3);
-        let d = four_runs_from_initial(2024);
+        let a = four_runs_from_initial(1, 2000);
+        let b = four_runs_from_initial(2, 2000);
+        let c = four_runs_from_initial(3, 2000);
+        let d = four_runs_from_initial(2024, 2000);
 
         assert_eq!(a.get(&(-2, 1, -1, 3)), Some(&7));
         assert_eq!(b.get(&(-2, 1, -1, 3)), Some(&7));
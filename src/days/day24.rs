@@ -4,22 +4,45 @@ use std::{
     str::FromStr,
 };
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::parse::{complete, key_value, lines, map, or, pair, terminated, try_map, uint, word},
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 24,
+    title: "Crossed Wires",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let mut wires: Wires = input.parse()?;
+    type Parsed = Wires;
 
-        let part1 = wires.z_value().to_string();
-        let part2 = Some(part2(wires));
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("4"),
+        part2: None,
+    }];
+
+    fn parse(&self, input: &str) -> Result<Wires, Error> {
+        input.parse()
+    }
 
-        Ok(DayResult { part1, part2 })
+    fn part1(&self, wires: &Wires, _ctx: &Context) -> Result<String, Error> {
+        Ok(wires.clone().z_value()?.to_string())
+    }
+
+    fn part2(&self, wires: &Wires, ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(part2(wires.clone(), ctx)?))
     }
 }
 
-fn part2(mut wires: Wires) -> String {
+fn part2(mut wires: Wires, ctx: &Context) -> Result<String, Error> {
     let mut swaps: Vec<(usize, usize)> = Vec::new();
     let mut correct: HashSet<usize> = HashSet::new();
     let mut inputs = HashSet::new();
@@ -31,7 +54,8 @@ fn part2(mut wires: Wires) -> String {
             let b = 1 << j;
             let c = a - b;
 
-            if b + c != wires.add_x_y(b, c) {
+            // Gates that form a cycle can't add either.
+            if wires.add_x_y(b, c).ok() != Some(b + c) {
                 has_error = true;
             }
         }
@@ -43,6 +67,8 @@ fn part2(mut wires: Wires) -> String {
     let mut last_skip = 0;
     let mut last_correct = HashSet::new();
     while i < MAX_BIT_IN {
+        ctx.check()?;
+        ctx.progress(i, MAX_BIT_IN);
         inputs.insert(format!("x{:0>2}", i));
         inputs.insert(format!("y{:0>2}", i));
 
@@ -107,10 +133,11 @@ fn part2(mut wires: Wires) -> String {
         swapped.push(wires.gates[y].out.to_owned());
     }
     swapped.sort();
-    swapped.join(",")
+    Ok(swapped.join(","))
 }
 
-struct Wires {
+#[derive(Clone)]
+pub struct Wires {
     state: HashMap<String, bool>,
     gates: Vec<LogicGate>,
 }
@@ -119,7 +146,7 @@ const MAX_BIT_IN: usize = 45;
 const MAX_XY: usize = 1 << (MAX_BIT_IN + 1);
 
 impl Wires {
-    fn z_value(&mut self) -> usize {
+    fn z_value(&mut self) -> Result<usize, Error> {
         let mut remaining: Vec<_> = self.gates.iter().collect();
         let mut remaining_len = remaining.len();
 
@@ -138,7 +165,7 @@ impl Wires {
                 }
             }
             if remaining.len() == remaining_len {
-                return usize::MAX;
+                return Err(Error::unsolvable("the gates form a cycle"));
             }
             remaining_len = remaining.len();
         }
@@ -153,10 +180,10 @@ impl Wires {
             }
             i <<= 1;
         }
-        t
+        Ok(t)
     }
 
-    fn add_x_y(&mut self, x: usize, y: usize) -> usize {
+    fn add_x_y(&mut self, x: usize, y: usize) -> Result<usize, Error> {
         assert!(x < MAX_XY);
         assert!(y < MAX_XY);
         self.state.clear();
@@ -203,31 +230,44 @@ impl Wires {
 }
 
 impl FromStr for Wires {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split("\n\n");
-        let initial_state = parts.next().ok_or("missing initial state")?;
-        let gates = parts.next().ok_or("missing gates")?;
-
-        let mut state = HashMap::new();
-        for s in initial_state.lines() {
-            let mut splits = s.split(": ");
-            let wire = splits.next().ok_or("missing wire")?;
-            let on_off = splits.next().ok_or("missing on off")?;
-
-            state.insert(wire.to_owned(), on_off == "1");
-        }
-        let gates = gates
-            .lines()
-            .map(|l| l.parse::<LogicGate>())
-            .collect::<Result<_, _>>()?;
+        let wire = || map(word(), str::to_owned);
+        let on_off = try_map(uint::<u8>(), |n| match n {
+            0 | 1 => Ok(n == 1),
+            _ => Err("expected 0 or 1"),
+        });
+        let operator = or(
+            map("AND", |_| Operator::And),
+            or(map("XOR", |_| Operator::Xor), map("OR", |_| Operator::Or)),
+        );
+        let gate = map(
+            pair(
+                pair(terminated(wire(), " "), terminated(operator, " ")),
+                pair(terminated(wire(), " -> "), wire()),
+            ),
+            |((left, operator), (right, out))| LogicGate {
+                left,
+                right,
+                out,
+                operator,
+            },
+        );
+        let (state, gates) = complete(
+            s,
+            pair(
+                terminated(lines(key_value(wire(), on_off)), "\n\n"),
+                lines(gate),
+            ),
+        )?;
+        let state = state.into_iter().collect();
 
         Ok(Self { state, gates })
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct LogicGate {
     left: String,
     right: String,
@@ -235,7 +275,7 @@ struct LogicGate {
     operator: Operator,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Operator {
     And,
     Or,
@@ -252,34 +292,35 @@ impl Operator {
     }
 }
 
-impl FromStr for LogicGate {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(" ");
-        let left = parts.next().ok_or("missing left")?.to_owned();
-        let operator = parts.next().ok_or("missing left")?.parse()?;
-        let right = parts.next().ok_or("missing left")?.to_owned();
-        parts.next();
-        let out = parts.next().ok_or("missing left")?.to_owned();
-
-        Ok(Self {
-            left,
-            right,
-            out,
-            operator,
-        })
-    }
-}
-
-impl FromStr for Operator {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "OR" => Ok(Operator::Or),
-            "XOR" => Ok(Operator::Xor),
-            "AND" => Ok(Operator::And),
-            unknown => Err(format!("Unknown operator: {}", unknown)),
-        }
+const EXAMPLE: &str = "x00: 1
+x01: 1
+x02: 1
+y00: 0
+y01: 1
+y02: 0
+
+x00 AND y00 -> z00
+x01 XOR y01 -> z01
+x02 OR y02 -> z02
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let wires = Instance.parse(EXAMPLE).unwrap();
+        assert_eq!(
+            Instance.part1(&wires, &Context::default()),
+            Ok("4".to_owned())
+        );
+
+        let cycle = "x00: 1\ny00: 0\n\nx00 AND a -> b\ny00 OR b -> a\n";
+        let wires = Instance.parse(cycle).unwrap();
+        assert_eq!(
+            Instance.part1(&wires, &Context::default()),
+            Err(Error::unsolvable("the gates form a cycle"))
+        );
     }
 }
@@ -1,19 +1,40 @@
 use std::str::FromStr;
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::{grid::Grid, parse_blocks},
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 25,
+    title: "Code Chronicle",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let lock_keys = input
-            .split("\n\n")
-            .map(|s| s.parse::<LockKey>())
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let part1 = count_matches(&lock_keys).to_string();
-        let part2 = Some("Merry Christmas!".to_owned());
-        Ok(DayResult { part1, part2 })
+    type Parsed = Vec<LockKey>;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("3"),
+        part2: Some("Merry Christmas!"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Vec<LockKey>, Error> {
+        parse_blocks(input)
+    }
+
+    fn part1(&self, lock_keys: &Vec<LockKey>, _ctx: &Context) -> Result<String, Error> {
+        Ok(count_matches(lock_keys).to_string())
+    }
+
+    fn part2(&self, _: &Vec<LockKey>, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some("Merry Christmas!".to_owned()))
     }
 }
 
@@ -39,57 +60,57 @@ fn count_matches(lock_keys: &[LockKey]) -> usize {
     count
 }
 
-struct Lock {
+pub struct Lock {
     pins: [u8; 5],
 }
 
-struct Key {
+pub struct Key {
     height: [u8; 5],
 }
 
-enum LockKey {
+pub enum LockKey {
     Lock(Lock),
     Key(Key),
 }
 
+/// The number of `#` in each of the five columns of a lock or key.
+fn column_heights(s: &str) -> Result<[u8; 5], Error> {
+    let grid = Grid::parse_with(s, |_, c| matches!(c, '#' | '.').then_some(c == '#'))?;
+    if grid.width() != 5 {
+        return Err(Error::parse(
+            s,
+            format!("expected 5 columns, found {}", grid.width()),
+        ));
+    }
+    let mut heights = [0; 5];
+    for (coord, _) in grid.iter().filter(|(_, &filled)| filled) {
+        heights[coord.x as usize] += 1;
+    }
+    Ok(heights)
+}
+
 impl FromStr for Key {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut height = [0; 5];
-
-        for l in s.lines() {
-            for (i, c) in l.chars().enumerate() {
-                if c == '#' {
-                    height[i] += 1;
-                }
-            }
-        }
-
-        Ok(Key { height })
+        Ok(Key {
+            height: column_heights(s)?,
+        })
     }
 }
 
 impl FromStr for Lock {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut pins = [0; 5];
-
-        for l in s.lines() {
-            for (i, c) in l.chars().enumerate() {
-                if c == '#' {
-                    pins[i] += 1;
-                }
-            }
-        }
-
-        Ok(Lock { pins })
+        Ok(Lock {
+            pins: column_heights(s)?,
+        })
     }
 }
 
 impl FromStr for LockKey {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.starts_with("#") {
@@ -111,12 +132,7 @@ impl Key {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn test() {
-        let input = "#####
+const EXAMPLE: &str = "#####
 .####
 .####
 .####
@@ -155,8 +171,14 @@ mod tests {
 #.#..
 #.#.#
 #####
-"
-        .to_owned();
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test() {
+        let input = EXAMPLE.to_owned();
         assert_eq!(
             Instance.run(input),
             Ok(DayResult {
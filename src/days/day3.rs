@@ -1,68 +1,67 @@
 use std::str::FromStr;
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::parse::{complete, delimited, map, or, pair, scan, terminated, uint},
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 3,
+    title: "Mull It Over",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let code = input.parse::<CorruptedCode>()?;
+    type Parsed = CorruptedCode;
 
-        let part1 = code.mul_always().to_string();
-        let part2 = Some(code.mul_if_enabled().to_string());
-        Ok(DayResult { part1, part2 })
-    }
-}
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("161"),
+        part2: Some("48"),
+    }];
 
-struct CorruptedCode {
-    line: String,
-}
+    fn parse(&self, input: &str) -> Result<CorruptedCode, Error> {
+        input.parse()
+    }
 
-impl FromStr for CorruptedCode {
-    type Err = String;
+    fn part1(&self, code: &CorruptedCode, _ctx: &Context) -> Result<String, Error> {
+        Ok(code.mul_always().to_string())
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(CorruptedCode { line: s.to_owned() })
+    fn part2(&self, code: &CorruptedCode, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(code.mul_if_enabled().to_string()))
     }
 }
 
-struct Line<'a> {
-    remaining: &'a str,
+pub struct CorruptedCode {
+    instructions: Vec<Instruction>,
 }
 
-impl<'a> Line<'a> {
-    fn new(s: &'a str) -> Self {
-        Line { remaining: s }
-    }
-
-    fn match_str(&mut self, s: &str) -> bool {
-        if self.remaining.starts_with(s) {
-            self.remaining = &self.remaining[s.len()..];
-            true
-        } else {
-            false
-        }
-    }
-
-    fn match_num(&mut self) -> usize {
-        let digits = self
-            .remaining
-            .chars()
-            .take_while(|c| c.is_digit(10))
-            .count();
-        let num = self.remaining[..digits]
-            .parse::<usize>()
-            .unwrap_or_default();
-        self.remaining = &self.remaining[digits..];
-        num
-    }
+enum Instruction {
+    Mul(usize, usize),
+    Do,
+    Dont,
+}
 
-    fn next_char(&mut self) {
-        self.remaining = &self.remaining[1..];
-    }
+impl FromStr for CorruptedCode {
+    type Err = Error;
 
-    fn has_remaining(&self) -> bool {
-        self.remaining.len() > 0
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mul = delimited("mul(", pair(terminated(uint(), ","), uint()), ")");
+        let instruction = or(
+            map(mul, |(a, b)| Instruction::Mul(a, b)),
+            or(
+                map("do()", |_| Instruction::Do),
+                map("don't()", |_| Instruction::Dont),
+            ),
+        );
+        let instructions = complete(s, scan(instruction))?;
+        Ok(CorruptedCode { instructions })
     }
 }
 
@@ -77,28 +76,14 @@ impl CorruptedCode {
 
     fn mul(&self, respect_enabled: bool) -> usize {
         let mut result = 0;
-        let mut line = Line::new(&self.line);
         let mut enabled = true;
 
-        while line.has_remaining() {
-            if respect_enabled && enabled && line.match_str("don't()") {
-                enabled = false;
-            } else if line.match_str("do()") {
-                enabled = true;
-            } else if enabled && line.match_str("mul(") {
-                let num1 = line.match_num();
-                if !line.match_str(",") {
-                    continue;
-                }
-
-                let num2 = line.match_num();
-                if !line.match_str(")") {
-                    continue;
-                }
-
-                result += num1 * num2;
-            } else {
-                line.next_char();
+        for instruction in &self.instructions {
+            match instruction {
+                Instruction::Mul(a, b) if enabled => result += a * b,
+                Instruction::Mul(..) => {}
+                Instruction::Do => enabled = true,
+                Instruction::Dont => enabled = !respect_enabled,
             }
         }
 
@@ -106,15 +91,15 @@ impl CorruptedCode {
     }
 }
 
+const EXAMPLE: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_corrupted_code() {
-        let code = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"
-            .parse::<CorruptedCode>()
-            .unwrap();
+        let code = EXAMPLE.parse::<CorruptedCode>().unwrap();
         assert_eq!(code.mul_always(), 161);
         assert_eq!(code.mul_if_enabled(), 48);
     }
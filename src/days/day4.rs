@@ -1,40 +1,54 @@
-use std::{collections::HashMap, str::FromStr};
+use std::str::FromStr;
 
-use super::day::*;
+use super::{day::*, error::Error, util::grid::Grid};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 4,
+    title: "Ceres Search",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let search = input.parse::<WordSearch>()?;
+    type Parsed = WordSearch;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("18"),
+        part2: Some("9"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<WordSearch, Error> {
+        input.parse()
+    }
+
+    fn part1(&self, search: &WordSearch, _ctx: &Context) -> Result<String, Error> {
+        Ok(search.count_xmas().to_string())
+    }
 
-        let part1 = search.count_xmas().to_string();
-        let part2 = Some(search.count_mas_x().to_string());
-        Ok(DayResult { part1, part2 })
+    fn part2(&self, search: &WordSearch, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(search.count_mas_x().to_string()))
     }
 }
 
-struct WordSearch {
-    grid: HashMap<(usize, usize), char>,
+pub struct WordSearch {
+    grid: Grid<char>,
 }
 
 impl WordSearch {
     fn count_xmas(&self) -> usize {
         let mut count = 0;
         let word = "XMAS".chars().collect::<Vec<_>>();
-        for &(x, y) in self.grid.keys() {
-            for (dx, dy) in (-1..=1)
-                .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
-                .filter(|(dx, dy)| *dx != 0 || *dy != 0)
-            {
-                let found = word.iter().enumerate().all(|(i, &c)| {
-                    let x = x as isize + dx * i as isize;
-                    let y = y as isize + dy * i as isize;
-                    if x < 0 || y < 0 {
-                        return false;
-                    }
-                    self.grid.get(&(x as usize, y as usize)) == Some(&c)
-                });
+        for start in self.grid.coords() {
+            for neighbour in start.neighbours8() {
+                let (dx, dy) = (neighbour.x - start.x, neighbour.y - start.y);
+                let found = word
+                    .iter()
+                    .zip(0..)
+                    .all(|(&c, i)| self.grid.get(start.offset(dx * i, dy * i)) == Some(&c));
 
                 if found {
                     count += 1;
@@ -49,22 +63,17 @@ impl WordSearch {
 
         let searches = ["MAS", "SAM"]
             .into_iter()
-            .map(|s| s.chars().enumerate().map(|(i, c)| (c, (i, i))))
+            .map(|s| s.chars().zip(0..).map(|(c, i)| (c, (i, i))))
             .flat_map(|s| {
-                ["SM", "MS"].into_iter().map(move |s2| {
-                    s.clone()
-                        .chain(s2.chars().zip([(0, 2), (2, 0)]).map(|(c, i)| (c, i)))
-                })
+                ["SM", "MS"]
+                    .into_iter()
+                    .map(move |s2| s.clone().chain(s2.chars().zip([(0, 2), (2, 0)])))
             });
 
-        for &(x, y) in self.grid.keys() {
+        for start in self.grid.coords() {
             for mut search in searches.clone() {
-                let found = search.all(|(c, (dx, dy))| {
-                    let x = x + dx;
-                    let y = y + dy;
-
-                    self.grid.get(&(x, y)) == Some(&c)
-                });
+                let found =
+                    search.all(|(c, (dx, dy))| self.grid.get(start.offset(dx, dy)) == Some(&c));
 
                 if found {
                     count += 1;
@@ -76,25 +85,14 @@ impl WordSearch {
 }
 
 impl FromStr for WordSearch {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut grid = HashMap::new();
-        for (y, line) in s.lines().enumerate() {
-            for (x, char) in line.chars().enumerate() {
-                grid.insert((x, y), char);
-            }
-        }
-        Ok(WordSearch { grid })
+        Ok(WordSearch { grid: s.parse()? })
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn test_example() {
-        let input = "MMMSXXMASM
+const EXAMPLE: &str = "MMMSXXMASM
 MSAMXMSMSA
 AMXSXMAAMM
 MSAMASMSMX
@@ -103,8 +101,14 @@ XXAMMXXAMA
 SMSMSASXSS
 SAXAMASAAA
 MAMMMXMMMM
-MXMXAXMASX"
-            .to_owned();
+MXMXAXMASX";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_example() {
+        let input = EXAMPLE.to_owned();
         let result = Instance.run(input);
         assert_eq!(
             result,
@@ -1,44 +1,58 @@
 use std::str::FromStr;
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::parse::{complete, lines, map, pair, separated, terminated, uint},
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 5,
+    title: "Print Queue",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let manual: Manual = input.parse()?;
+    type Parsed = Manual;
 
-        let part1 = manual.ordeded().to_string();
-        let part2 = Some(manual.fix_unordered().to_string());
-        Ok(DayResult { part1, part2 })
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("143"),
+        part2: Some("123"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Manual, Error> {
+        input.parse()
+    }
+
+    fn part1(&self, manual: &Manual, _ctx: &Context) -> Result<String, Error> {
+        Ok(manual.ordeded().to_string())
+    }
+
+    fn part2(&self, manual: &Manual, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(manual.fix_unordered().to_string()))
     }
 }
 
 #[derive(Debug)]
-struct Manual {
+pub struct Manual {
     rules: Vec<Rule>,
     pages: Vec<Pages>,
 }
 
 impl FromStr for Manual {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut rules = Vec::new();
-        let mut pages = Vec::new();
-
-        let mut lines = s.lines();
-        for line in &mut lines {
-            if line.is_empty() {
-                break;
-            }
-            rules.push(line.parse()?);
-        }
-
-        for line in lines {
-            pages.push(line.parse()?);
-        }
-
+        let rule = map(pair(terminated(uint(), "|"), uint()), |(before, after)| {
+            Rule { before, after }
+        });
+        let pages = map(separated(uint(), ","), |order| Pages { order });
+        let (rules, pages) = complete(s, pair(terminated(lines(rule), "\n\n"), lines(pages)))?;
         Ok(Manual { rules, pages })
     }
 }
@@ -49,45 +63,11 @@ struct Rule {
     after: u8,
 }
 
-impl FromStr for Rule {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split("|");
-        let before = parts
-            .next()
-            .unwrap()
-            .parse()
-            .map_err(|e| format!("failed to parse before in '{}': {}", s, e))?;
-        let after = parts
-            .next()
-            .unwrap()
-            .parse()
-            .map_err(|e| format!("failed to parse after in '{}': {}", s, e))?;
-        Ok(Rule { before, after })
-    }
-}
-
 #[derive(Debug, Clone)]
 struct Pages {
     order: Vec<u8>,
 }
 
-impl FromStr for Pages {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let order = s
-            .split(",")
-            .map(|n| {
-                n.parse::<u8>()
-                    .map_err(|e| format!("failed to parse page in '{}': {}", s, e))
-            })
-            .collect::<Result<_, _>>()?;
-        Ok(Pages { order })
-    }
-}
-
 impl Manual {
     fn ordeded(&self) -> usize {
         let mut total = 0;
@@ -148,13 +128,7 @@ impl Pages {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_example() {
-        let input = "47|53
+const EXAMPLE: &str = "47|53
 97|13
 97|61
 97|47
@@ -181,8 +155,15 @@ mod tests {
 75,29,13
 75,97,47,61,53
 61,13,29
-97,13,75,29,47"
-            .to_owned();
+97,13,75,29,47";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = EXAMPLE.to_owned();
         let result = Instance.run(input).unwrap();
         assert_eq!(result.part1, "143");
         assert_eq!(result.part2, Some("123".to_owned()));
@@ -1,178 +1,124 @@
 use std::{collections::HashSet, str::FromStr};
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::grid::{Coord, Direction, Grid},
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 6,
+    title: "Guard Gallivant",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let map: Map = input.parse()?;
-        let part1 = map.guard_path().to_string();
-        let part2 = Some(count_loops(&map).to_string());
-        Ok(DayResult { part1, part2 })
+    type Parsed = Map;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("41"),
+        part2: Some("6"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Map, Error> {
+        input.parse()
+    }
+
+    fn part1(&self, map: &Map, _ctx: &Context) -> Result<String, Error> {
+        Ok(map.guard_path().to_string())
+    }
+
+    fn part2(&self, map: &Map, ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(count_loops(map, ctx)?.to_string()))
     }
 }
 
 #[derive(Debug, Clone)]
-struct Map {
+pub struct Map {
     guard: Coord,
     guard_direction: Direction,
-    obstacles: HashSet<Coord>,
-    max_x: i32,
-    max_y: i32,
+    obstacles: Grid<bool>,
 }
 
 impl FromStr for Map {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut obstacles = HashSet::new();
         let mut guard = None;
-        let mut guard_direction = None;
-        let mut max_x = 0;
-        let mut max_y = 0;
-
-        for (y, line) in s.lines().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                let coord = Coord {
-                    x: x as i32,
-                    y: y as i32,
-                };
-                match c {
-                    '#' => {
-                        obstacles.insert(coord);
-                    }
-                    '^' => {
-                        guard = Some(coord);
-                        guard_direction = Some(Direction::Up);
-                    }
-                    _ => {}
-                }
-                max_x = x as i32;
+        let obstacles = Grid::parse_with(s, |coord, c| {
+            if let Some(direction) = Direction::from_arrow(c) {
+                guard = Some((coord, direction));
             }
-            max_y = y as i32;
-        }
+            Some(c == '#')
+        })?;
+        let (guard, guard_direction) = guard.ok_or_else(|| Error::parse("", "Guard not found"))?;
 
         Ok(Map {
-            guard: guard.ok_or("Guard not found")?,
-            guard_direction: guard_direction.ok_or("Guard direction not found")?,
+            guard,
+            guard_direction,
             obstacles,
-            max_x,
-            max_y,
         })
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct Coord {
-    x: i32,
-    y: i32,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 impl Map {
     fn guard_path(&self) -> usize {
         self.guard_path_looped().map_or(0, |s| s.len())
     }
 
     fn guard_path_looped(&self) -> Option<HashSet<Coord>> {
-        let mut guard = self.guard.clone();
+        let mut guard = self.guard;
         let mut direction = self.guard_direction;
-        let min_x = 0;
-        let min_y = 0;
         let mut seen_spaces = HashSet::new();
-        seen_spaces.insert(guard.clone());
+        seen_spaces.insert(guard);
         let mut seen_states = HashSet::new();
-        seen_states.insert((guard.clone(), direction));
-        loop {
-            let next_guard = guard.move_one(direction);
-            if next_guard.x < min_x
-                || next_guard.x > self.max_x
-                || next_guard.y < min_y
-                || next_guard.y > self.max_y
-            {
+        // Turning counts as a new state too, so a guard boxed in by obstacles loops.
+        while seen_states.insert((guard, direction)) {
+            let next_guard = guard.step(direction);
+            let Some(&obstacle) = self.obstacles.get(next_guard) else {
                 return Some(seen_spaces);
-            }
-            let state = (next_guard.clone(), direction);
-            if seen_states.contains(&state) {
-                return None;
-            }
-            if !self.obstacles.contains(&next_guard) {
+            };
+            if !obstacle {
                 guard = next_guard;
-                seen_spaces.insert(guard.clone());
-                seen_states.insert(state);
+                seen_spaces.insert(guard);
             } else {
-                direction = direction.turn_right();
+                direction = direction.clockwise();
             }
         }
+        None
     }
 }
 
-impl Direction {
-    fn turn_right(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-        }
-    }
-}
-
-impl Coord {
-    fn move_one(&self, direction: Direction) -> Coord {
-        let mut coord = self.clone();
-        match direction {
-            Direction::Up => {
-                coord.y -= 1;
-            }
-            Direction::Down => {
-                coord.y += 1;
-            }
-            Direction::Left => {
-                coord.x -= 1;
-            }
-            Direction::Right => {
-                coord.x += 1;
-            }
-        }
-        coord
-    }
-}
-
-fn count_loops(map: &Map) -> usize {
+fn count_loops(map: &Map, ctx: &Context) -> Result<usize, Error> {
     let mut loops = 0;
-    let mut candidates = map.guard_path_looped().unwrap();
-    candidates.remove(&map.guard.clone());
+    let mut candidates = map
+        .guard_path_looped()
+        .ok_or_else(|| Error::unsolvable("the guard never leaves the map"))?;
+    candidates.remove(&map.guard);
     let mut map = map.clone();
 
-    for coord in candidates {
-        if map.obstacles.contains(&coord) {
+    let total = candidates.len();
+    for (i, coord) in candidates.into_iter().enumerate() {
+        ctx.check()?;
+        ctx.progress(i, total);
+        if map.obstacles[coord] {
             continue;
         }
-        map.obstacles.insert(coord.clone());
+        map.obstacles[coord] = true;
         if map.guard_path_looped().is_none() {
             loops += 1;
         }
-        map.obstacles.remove(&coord);
+        map.obstacles[coord] = false;
     }
-    loops
+    Ok(loops)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test() {
-        let input = "....#.....
+const EXAMPLE: &str = "....#.....
 .........#
 ..........
 ..#.......
@@ -181,11 +127,27 @@ mod tests {
 .#..^.....
 ........#.
 #.........
-......#..."
-            .to_owned();
+......#...";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let input = EXAMPLE.to_owned();
         let day = Instance;
         let result = day.run(input).unwrap();
         assert_eq!(result.part1, "41");
         assert_eq!(result.part2, Some(6.to_string()));
     }
+
+    #[test]
+    fn test_trapped_guard() {
+        let map = Instance.parse(".#.\n#^#\n.#.").unwrap();
+        assert_eq!(
+            Instance.part2(&map, &Context::default()),
+            Err(Error::unsolvable("the guard never leaves the map"))
+        );
+    }
 }
@@ -1,58 +1,69 @@
 use std::str::FromStr;
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::{
+        parse::{complete, pair, separated, terminated, uint},
+        parse_lines,
+    },
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 7,
+    title: "Bridge Repair",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let parsed: Vec<Equation> = input
-            .lines()
-            .map(|line| line.parse())
-            .collect::<Result<_, _>>()?;
+    type Parsed = Vec<Equation>;
 
-        let part1 = parsed
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("3749"),
+        part2: Some("11387"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Vec<Equation>, Error> {
+        parse_lines(input)
+    }
+
+    fn part1(&self, equations: &Vec<Equation>, _ctx: &Context) -> Result<String, Error> {
+        let part1 = equations
             .iter()
             .filter(|eq| eq.solves(false))
             .map(|eq| eq.answer)
             .sum::<usize>()
             .to_string();
-        let part2 = parsed
+        Ok(part1)
+    }
+
+    fn part2(&self, equations: &Vec<Equation>, _ctx: &Context) -> Result<Option<String>, Error> {
+        let part2 = equations
             .iter()
             .filter(|eq| eq.solves(true))
             .map(|eq| eq.answer)
             .sum::<usize>()
             .to_string();
-        Ok(DayResult {
-            part1,
-            part2: Some(part2),
-        })
+        Ok(Some(part2))
     }
 }
 
-struct Equation {
+pub struct Equation {
     answer: usize,
     numbers: Vec<usize>,
 }
 
 impl FromStr for Equation {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (answer_str, numbers_str) = s.split_once(": ").ok_or("missing ': '")?;
-
-        let mut numbers = Vec::new();
-        for part in numbers_str.split_whitespace() {
-            numbers.push(
-                part.parse()
-                    .map_err(|e| format!("bad number format for {}: {}", part, e))?,
-            );
-        }
-
-        let answer = answer_str
-            .parse()
-            .map_err(|e| format!("bad answer format for {}: {}", answer_str, e))?;
-
+        let (answer, numbers) =
+            complete(s, pair(terminated(uint(), ": "), separated(uint(), " ")))?;
         Ok(Equation { answer, numbers })
     }
 }
@@ -114,6 +125,16 @@ impl Equation {
     }
 }
 
+const EXAMPLE: &str = "190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20";
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,15 +154,7 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let input = "190: 10 19
-3267: 81 40 27
-83: 17 5
-156: 15 6
-7290: 6 8 6 15
-161011: 16 10 13
-192: 17 8 14
-21037: 9 7 18 13
-292: 11 6 16 20";
+        let input = EXAMPLE;
         let day = Instance;
         let result = day.run(input.to_owned()).unwrap();
         assert_eq!(result.part1, "3749");
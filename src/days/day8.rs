@@ -3,61 +3,54 @@ use std::{
     str::FromStr,
 };
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::grid::{Coord, Grid},
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 8,
+    title: "Resonant Collinearity",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let antennas: Antennas = input.parse()?;
-        let part1 = antennas.count_antinodes().to_string();
-        let part2 = Some(antennas.count_antinodes_all().to_string());
-        Ok(DayResult { part1, part2 })
+    type Parsed = Antennas;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("14"),
+        part2: Some("34"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<Antennas, Error> {
+        input.parse()
     }
-}
 
-#[derive(Debug)]
-struct Antennas {
-    antennas: Vec<Antenna>,
-    max_x: isize,
-    max_y: isize,
+    fn part1(&self, antennas: &Antennas, _ctx: &Context) -> Result<String, Error> {
+        Ok(antennas.count_antinodes().to_string())
+    }
+
+    fn part2(&self, antennas: &Antennas, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(antennas.count_antinodes_all().to_string()))
+    }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct Antenna {
-    x: isize,
-    y: isize,
-    frequency: char,
+#[derive(Debug)]
+pub struct Antennas {
+    grid: Grid<char>,
 }
 
 impl FromStr for Antennas {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut antennas = Vec::new();
-        let mut max_x = 0;
-        let mut max_y = 0;
-
-        for (y, line) in s.lines().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                max_x = x as isize;
-                if c == '.' {
-                    continue;
-                }
-                antennas.push(Antenna {
-                    x: x as isize,
-                    y: y as isize,
-                    frequency: c,
-                });
-            }
-            max_y = y as isize;
-        }
-
-        Ok(Antennas {
-            antennas,
-            max_x,
-            max_y,
-        })
+        Ok(Antennas { grid: s.parse()? })
     }
 }
 
@@ -65,20 +58,15 @@ impl Antennas {
     fn count_antinodes(&self) -> usize {
         let mut antinodes = HashSet::new();
         for groups in &self.grouped_antennas() {
-            for a in groups {
-                for b in groups {
+            for &a in groups {
+                for &b in groups {
                     if a == b {
                         continue;
                     }
-                    let dx = a.x - b.x;
-                    let dy = a.y - b.y;
-
-                    let x = a.x + dx;
-                    let y = a.y + dy;
-                    if x < 0 || y < 0 || x > self.max_x || y > self.max_y {
-                        continue;
+                    let antinode = a.offset(a.x - b.x, a.y - b.y);
+                    if self.grid.contains(antinode) {
+                        antinodes.insert(antinode);
                     }
-                    antinodes.insert((x, y));
                 }
             }
         }
@@ -88,20 +76,15 @@ impl Antennas {
     fn count_antinodes_all(&self) -> usize {
         let mut antinodes = HashSet::new();
         for groups in &self.grouped_antennas() {
-            for a in groups {
-                for b in groups {
+            for &a in groups {
+                for &b in groups {
                     if a == b {
                         continue;
                     }
-                    let dx = a.x - b.x;
-                    let dy = a.y - b.y;
-
-                    let mut x = a.x;
-                    let mut y = a.y;
-                    while x >= 0 && y >= 0 && x <= self.max_x && y <= self.max_y {
-                        antinodes.insert((x, y));
-                        x += dx;
-                        y += dy;
+                    let mut antinode = a;
+                    while self.grid.contains(antinode) {
+                        antinodes.insert(antinode);
+                        antinode = antinode.offset(a.x - b.x, a.y - b.y);
                     }
                 }
             }
@@ -109,25 +92,20 @@ impl Antennas {
         antinodes.len()
     }
 
-    fn grouped_antennas(&self) -> Vec<Vec<&Antenna>> {
+    /// The positions of the antennas of each frequency.
+    fn grouped_antennas(&self) -> Vec<Vec<Coord>> {
         let mut antennas = HashMap::new();
-        for antenna in &self.antennas {
+        for (coord, &frequency) in self.grid.iter().filter(|(_, &c)| c != '.') {
             antennas
-                .entry(antenna.frequency)
-                .and_modify(|v: &mut Vec<_>| v.push(antenna))
-                .or_insert(vec![antenna]);
+                .entry(frequency)
+                .and_modify(|v: &mut Vec<_>| v.push(coord))
+                .or_insert(vec![coord]);
         }
         antennas.into_values().collect()
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test() {
-        let input = "............
+const EXAMPLE: &str = "............
 ........0...
 .....0......
 .......0....
@@ -139,8 +117,15 @@ mod tests {
 .........A..
 ............
 ............
-"
-        .to_owned();
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let input = EXAMPLE.to_owned();
         let expected = DayResult {
             part1: "14".to_owned(),
             part2: Some("34".to_owned()),
@@ -1,37 +1,59 @@
-use std::{iter, str::FromStr};
+use std::str::FromStr;
 
-use super::day::*;
+use super::{
+    day::*,
+    error::Error,
+    util::parse::{complete, digit, many1},
+};
+
+pub const REGISTRATION: Registration = Registration {
+    year: 2024,
+    day: 9,
+    title: "Disk Fragmenter",
+    new: || Box::new(Instance),
+};
 
 pub struct Instance;
 
 impl Day for Instance {
-    fn run(&self, input: String) -> Result<DayResult, String> {
-        let map = input.parse::<DiskMap>()?;
-        let part1 = map.filesystem_checksum().to_string();
-        let part2 = Some(map.defragment().to_string());
-        Ok(DayResult { part1, part2 })
+    type Parsed = DiskMap;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: Some("1928"),
+        part2: Some("2858"),
+    }];
+
+    fn parse(&self, input: &str) -> Result<DiskMap, Error> {
+        input.parse()
+    }
+
+    fn part1(&self, map: &DiskMap, _ctx: &Context) -> Result<String, Error> {
+        Ok(map.filesystem_checksum().to_string())
+    }
+
+    fn part2(&self, map: &DiskMap, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(Some(map.defragment().to_string()))
     }
 }
 
-struct DiskMap {
+pub struct DiskMap {
     map: Vec<u32>,
 }
 
 impl FromStr for DiskMap {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let map = s
-            .chars()
-            .map(|c| c.to_digit(10).ok_or("invalid digit".to_owned()))
-            .collect::<Result<Vec<_>, _>>()?;
+        let map = complete(s, many1(digit()))?;
         Ok(DiskMap { map })
     }
 }
 
 impl DiskMap {
     fn filesystem_checksum(&self) -> usize {
-        let mut filesystem = FileSystem::from_disk_map(&self);
+        let mut filesystem = FileSystem::from_disk_map(self);
 
         while filesystem.has_free() {
             filesystem.move_one_left();
@@ -41,7 +63,7 @@ impl DiskMap {
     }
 
     fn defragment(&self) -> usize {
-        let mut filesystem = FileSystem::from_disk_map(&self);
+        let mut filesystem = FileSystem::from_disk_map(self);
 
         for i in (1..=filesystem.max_id).rev() {
             filesystem.move_block_left(i);
@@ -79,12 +101,12 @@ impl FileSystem {
     }
 
     fn add(&mut self, id: u32, amount: usize) {
-        self.data.extend(iter::repeat(Some(id)).take(amount));
+        self.data.extend(std::iter::repeat_n(Some(id), amount));
         self.max_id = self.max_id.max(id);
     }
 
     fn add_free(&mut self, amount: usize) {
-        self.data.extend(iter::repeat(None).take(amount));
+        self.data.extend(std::iter::repeat_n(None, amount));
         self.free_space += amount;
     }
 
@@ -141,13 +163,15 @@ impl FileSystem {
     }
 }
 
+const EXAMPLE: &str = "2333133121414131402";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test() {
-        let input = "2333133121414131402".to_owned();
+        let input = EXAMPLE.to_owned();
         let day = Instance;
         let result = day.run(input).unwrap();
         assert_eq!(result.part1, "1928");
@@ -0,0 +1,181 @@
+use std::{any::Any, fmt, io, time::Duration};
+
+#[derive(Debug)]
+pub enum Error {
+    Parse(ParseError),
+    Unsolvable(String),
+    InvalidConfig(String),
+    Io(io::Error),
+    /// The solver ran for longer than it was allowed to.
+    Timeout(Duration),
+    /// The solver panicked, with the panic's message.
+    Panic(String),
+}
+
+/// Where and why an input failed to parse. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub text: String,
+    pub message: String,
+}
+
+impl Error {
+    pub fn parse(text: &str, message: impl fmt::Display) -> Self {
+        Error::Parse(ParseError {
+            line: None,
+            column: None,
+            text: text.to_owned(),
+            message: message.to_string(),
+        })
+    }
+
+    pub fn unsolvable(message: impl fmt::Display) -> Self {
+        Error::Unsolvable(message.to_string())
+    }
+
+    pub fn invalid_config(message: impl fmt::Display) -> Self {
+        Error::InvalidConfig(message.to_string())
+    }
+
+    /// Wraps the payload of a caught panic, keeping its message when it has one.
+    pub fn panic(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "unknown cause".to_owned(),
+            },
+        };
+        Error::Panic(message)
+    }
+
+    /// Places a parse error on `line`. Errors that already carry a line were
+    /// raised on a section of the input starting at `line`, so the two are combined.
+    pub fn at_line(self, line: usize) -> Self {
+        match self {
+            Error::Parse(mut e) => {
+                e.line = Some(e.line.map_or(line, |l| l + line - 1));
+                Error::Parse(e)
+            }
+            e => e,
+        }
+    }
+
+    /// Places a parse error on `column`, combined like [`Error::at_line`].
+    pub fn at_column(self, column: usize) -> Self {
+        match self {
+            Error::Parse(mut e) => {
+                e.column = Some(e.column.map_or(column, |c| c + column - 1));
+                Error::Parse(e)
+            }
+            e => e,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Parse(_) => "parse",
+            Error::Unsolvable(_) => "unsolvable",
+            Error::InvalidConfig(_) => "invalid_config",
+            Error::Io(_) => "io",
+            Error::Timeout(_) => "timeout",
+            Error::Panic(_) => "panic",
+        }
+    }
+
+    /// Process exit code for the error, following the BSD `sysexits.h` conventions.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Parse(_) => 65,
+            Error::Unsolvable(_) => 1,
+            Error::InvalidConfig(_) => 78,
+            Error::Io(_) => 74,
+            Error::Timeout(_) => 75,
+            Error::Panic(_) => 70,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(e) => write!(f, "{}", e),
+            Error::Unsolvable(m) => write!(f, "no solution: {}", m),
+            Error::InvalidConfig(m) => write!(f, "invalid configuration: {}", m),
+            Error::Io(e) => write!(f, "i/o error: {}", e),
+            Error::Timeout(limit) => write!(f, "timed out after {:?}", limit),
+            Error::Panic(m) => write!(f, "panicked: {}", m),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parse error")?;
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, " at line {}, column {}", line, column)?,
+            (Some(line), None) => write!(f, " at line {}", line)?,
+            (None, Some(column)) => write!(f, " at column {}", column)?,
+            (None, None) => {}
+        }
+        write!(f, ": {}", self.message)?;
+        if !self.text.is_empty() {
+            write!(f, " in '{}'", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Error::Parse(a), Error::Parse(b)) => a == b,
+            (Error::Unsolvable(a), Error::Unsolvable(b)) => a == b,
+            (Error::InvalidConfig(a), Error::InvalidConfig(b)) => a == b,
+            (Error::Io(a), Error::Io(b)) => a.kind() == b.kind(),
+            (Error::Timeout(a), Error::Timeout(b)) => a == b,
+            (Error::Panic(a), Error::Panic(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_positions() {
+        let error = Error::parse("x", "bad").at_column(3).at_line(2).at_line(10);
+        assert_eq!(
+            error,
+            Error::Parse(ParseError {
+                line: Some(11),
+                column: Some(3),
+                text: "x".to_owned(),
+                message: "bad".to_owned(),
+            })
+        );
+        assert_eq!(
+            error.to_string(),
+            "parse error at line 11, column 3: bad in 'x'"
+        );
+    }
+
+    #[test]
+    fn test_position_ignored_for_other_kinds() {
+        let error = Error::unsolvable("stuck").at_line(4);
+        assert_eq!(error, Error::Unsolvable("stuck".to_owned()));
+        assert_eq!(error.exit_code(), 1);
+    }
+}
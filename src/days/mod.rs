@@ -1,16 +1,9 @@
-pub mod day;
-mod util;
+use std::{collections::HashMap, sync::Arc};
 
-pub mod day1;
-pub mod day2;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
-pub mod day9;
+use day::{Program, Registration};
 
+pub mod day;
+pub mod day1;
 pub mod day10;
 pub mod day11;
 pub mod day12;
@@ -21,10 +14,72 @@ pub mod day16;
 pub mod day17;
 pub mod day18;
 pub mod day19;
-
+pub mod day2;
 pub mod day20;
 pub mod day21;
 pub mod day22;
 pub mod day23;
 pub mod day24;
 pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod error;
+pub mod util;
+
+/// Collects the registrations of the given day modules into [`DAYS`].
+macro_rules! days {
+    ($($module:ident),* $(,)?) => {
+        /// Every registered day, in order.
+        pub const DAYS: &[Registration] = &[$($module::REGISTRATION),*];
+    };
+}
+
+days!(
+    day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13, day14, day15,
+    day16, day17, day18, day19, day20, day21, day22, day23, day24, day25,
+);
+
+/// Solvers for each day, keyed by year and day number.
+pub type Registry = HashMap<(i32, i32), Arc<dyn Program>>;
+
+/// A fresh instance of every registered day.
+pub fn registry() -> Registry {
+    DAYS.iter()
+        .map(|r| ((r.year, r.day), Arc::from((r.new)())))
+        .collect()
+}
+
+/// The most recent year with any registered days.
+pub fn latest_year() -> Option<i32> {
+    DAYS.iter().map(|r| r.year).max()
+}
+
+/// The registered days of `year`, in order.
+pub fn days_of(year: i32) -> impl Iterator<Item = i32> {
+    DAYS.iter().filter(move |r| r.year == year).map(|r| r.day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_are_unique_and_ordered() {
+        assert!(DAYS
+            .windows(2)
+            .all(|w| (w[0].year, w[0].day) < (w[1].year, w[1].day)));
+        assert_eq!(registry().len(), DAYS.len());
+        assert_eq!(latest_year(), DAYS.iter().map(|r| r.year).max());
+        for year in DAYS.iter().map(|r| r.year) {
+            let days: Vec<i32> = days_of(year).collect();
+            assert!(days.windows(2).all(|w| w[0] < w[1]));
+            assert!(days.iter().all(|day| (1..=25).contains(day)));
+        }
+        assert_eq!(days_of(0).count(), 0);
+    }
+}
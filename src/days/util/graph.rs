@@ -0,0 +1,351 @@
+use std::{collections::HashMap, hash::Hash};
+
+use super::search::bfs;
+
+/// An undirected graph, with each node interned to an id in `0..len()`.
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    names: Vec<N>,
+    ids: HashMap<N, usize>,
+    /// The neighbours of each node, sorted by id.
+    adjacency: Vec<Vec<usize>>,
+}
+
+/// The spread of the node degrees in a graph.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DegreeStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    pub fn new() -> Self {
+        Graph {
+            names: Vec::new(),
+            ids: HashMap::new(),
+            adjacency: Vec::new(),
+        }
+    }
+
+    /// The id of `name`, adding it as a node without edges if it's new.
+    pub fn add_node(&mut self, name: N) -> usize {
+        if let Some(&id) = self.ids.get(&name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.clone());
+        self.ids.insert(name, id);
+        self.adjacency.push(Vec::new());
+        id
+    }
+
+    /// Connects `a` and `b`, adding either if they're new. Repeated edges and
+    /// loops are ignored.
+    pub fn add_edge(&mut self, a: N, b: N) {
+        let (a, b) = (self.add_node(a), self.add_node(b));
+        if a == b {
+            return;
+        }
+        for (from, to) in [(a, b), (b, a)] {
+            let neighbours = &mut self.adjacency[from];
+            if let Err(i) = neighbours.binary_search(&to) {
+                neighbours.insert(i, to);
+            }
+        }
+    }
+
+    pub fn id(&self, name: &N) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+}
+
+impl<N> Graph<N> {
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn name(&self, id: usize) -> &N {
+        &self.names[id]
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    /// The neighbours of `id`, sorted by id.
+    pub fn neighbours(&self, id: usize) -> &[usize] {
+        &self.adjacency[id]
+    }
+
+    pub fn degree(&self, id: usize) -> usize {
+        self.adjacency[id].len()
+    }
+
+    pub fn is_adjacent(&self, a: usize, b: usize) -> bool {
+        self.adjacency[a].binary_search(&b).is_ok()
+    }
+
+    /// `None` for a graph without nodes.
+    pub fn degree_stats(&self) -> Option<DegreeStats> {
+        let degrees = self.adjacency.iter().map(Vec::len);
+        Some(DegreeStats {
+            min: degrees.clone().min()?,
+            max: degrees.clone().max()?,
+            mean: degrees.sum::<usize>() as f64 / self.len() as f64,
+        })
+    }
+
+    /// The nodes of each connected component, sorted by id, in the order of
+    /// their lowest id.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.len()];
+        let mut components = Vec::new();
+        for start in 0..self.len() {
+            if seen[start] {
+                continue;
+            }
+            let reached = bfs(start, |&id| self.neighbours(id).iter().copied(), |_| false);
+            let mut component: Vec<usize> = reached.into_costs().into_keys().collect();
+            component.sort_unstable();
+            for &id in &component {
+                seen[id] = true;
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// Every set of `k` nodes that are all connected to each other, sorted by id.
+    pub fn cliques(&self, k: usize) -> Vec<Vec<usize>> {
+        let mut cliques = Vec::new();
+        let everything: Vec<usize> = (0..self.len()).collect();
+        self.extend_cliques(&mut Vec::new(), &everything, k, &mut cliques);
+        cliques
+    }
+
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        self.cliques(3)
+            .into_iter()
+            .map(|c| [c[0], c[1], c[2]])
+            .collect()
+    }
+
+    /// Extends `clique` to size `k` from `candidates`, which are connected to
+    /// all of it and have higher ids, so that each clique is found only once.
+    fn extend_cliques(
+        &self,
+        clique: &mut Vec<usize>,
+        candidates: &[usize],
+        k: usize,
+        cliques: &mut Vec<Vec<usize>>,
+    ) {
+        if clique.len() == k {
+            cliques.push(clique.clone());
+            return;
+        }
+        for (i, &id) in candidates.iter().enumerate() {
+            if candidates.len() - i < k - clique.len() {
+                break;
+            }
+            let next = intersect(&candidates[i + 1..], self.neighbours(id));
+            clique.push(id);
+            self.extend_cliques(clique, &next, k, cliques);
+            clique.pop();
+        }
+    }
+
+    /// Calls `found` with every clique that can't be extended by another node,
+    /// sorted by id. A graph without nodes has none.
+    pub fn for_each_maximal_clique(&self, mut found: impl FnMut(&[usize])) {
+        if self.is_empty() {
+            return;
+        }
+        let everything = (0..self.len()).collect();
+        self.bron_kerbosch(&mut Vec::new(), everything, Vec::new(), &mut found);
+    }
+
+    /// One of the largest cliques, sorted by id.
+    pub fn maximum_clique(&self) -> Vec<usize> {
+        let mut largest = Vec::new();
+        self.for_each_maximal_clique(|clique| {
+            if clique.len() > largest.len() {
+                largest = clique.to_vec();
+            }
+        });
+        largest
+    }
+
+    /// Bron–Kerbosch with pivoting, where `clique` is being grown from the
+    /// `candidates`, and the `excluded` nodes would also extend it but have
+    /// already been tried. Both are kept sorted.
+    fn bron_kerbosch(
+        &self,
+        clique: &mut Vec<usize>,
+        mut candidates: Vec<usize>,
+        mut excluded: Vec<usize>,
+        found: &mut impl FnMut(&[usize]),
+    ) {
+        // Any maximal clique includes the pivot or one of its non-neighbours,
+        // so only those need trying. The best pivot rules out the most.
+        let Some(&pivot) = candidates
+            .iter()
+            .chain(&excluded)
+            .max_by_key(|&&id| intersect(&candidates, self.neighbours(id)).len())
+        else {
+            let mut clique = clique.clone();
+            clique.sort_unstable();
+            found(&clique);
+            return;
+        };
+        let tries: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&id| !self.is_adjacent(pivot, id))
+            .collect();
+        for id in tries {
+            let neighbours = self.neighbours(id);
+            clique.push(id);
+            self.bron_kerbosch(
+                clique,
+                intersect(&candidates, neighbours),
+                intersect(&excluded, neighbours),
+                found,
+            );
+            clique.pop();
+            if let Ok(i) = candidates.binary_search(&id) {
+                candidates.remove(i);
+            }
+            if let Err(i) = excluded.binary_search(&id) {
+                excluded.insert(i, id);
+            }
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash> Default for Graph<N> {
+    fn default() -> Self {
+        Graph::new()
+    }
+}
+
+/// Builds a graph from its edges.
+impl<N: Clone + Eq + Hash> FromIterator<(N, N)> for Graph<N> {
+    fn from_iter<I: IntoIterator<Item = (N, N)>>(edges: I) -> Self {
+        let mut graph = Graph::new();
+        for (a, b) in edges {
+            graph.add_edge(a, b);
+        }
+        graph
+    }
+}
+
+/// The ids in both sorted lists.
+fn intersect(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let (mut a, mut b) = (a.iter().peekable(), b.iter().peekable());
+    let mut both = Vec::new();
+    while let (Some(&&x), Some(&&y)) = (a.peek(), b.peek()) {
+        if x <= y {
+            a.next();
+        }
+        if y <= x {
+            b.next();
+        }
+        if x == y {
+            both.push(x);
+        }
+    }
+    both
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two triangles sharing the edge b-c, with d also connected to e, and a
+    /// separate edge x-y.
+    fn example() -> Graph<&'static str> {
+        [
+            ("a", "b"),
+            ("a", "c"),
+            ("b", "c"),
+            ("b", "d"),
+            ("c", "d"),
+            ("d", "e"),
+            ("x", "y"),
+            ("b", "a"),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn names(graph: &Graph<&'static str>, ids: &[usize]) -> Vec<&'static str> {
+        let mut names: Vec<_> = ids.iter().map(|&id| *graph.name(id)).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_graph() {
+        let graph = example();
+        assert_eq!(graph.len(), 7);
+        assert_eq!(graph.edge_count(), 7);
+        let (b, d) = (graph.id(&"b").unwrap(), graph.id(&"d").unwrap());
+        assert_eq!(names(&graph, graph.neighbours(b)), ["a", "c", "d"]);
+        assert!(graph.is_adjacent(b, d));
+        assert!(!graph.is_adjacent(graph.id(&"a").unwrap(), d));
+        assert_eq!(
+            graph.degree_stats(),
+            Some(DegreeStats {
+                min: 1,
+                max: 3,
+                mean: 2.0,
+            })
+        );
+
+        let components: Vec<_> = graph
+            .components()
+            .iter()
+            .map(|c| names(&graph, c))
+            .collect();
+        assert_eq!(components, [vec!["a", "b", "c", "d", "e"], vec!["x", "y"]]);
+    }
+
+    #[test]
+    fn test_cliques() {
+        let graph = example();
+        let mut triangles: Vec<_> = graph.triangles().iter().map(|t| names(&graph, t)).collect();
+        triangles.sort();
+        assert_eq!(triangles, [["a", "b", "c"], ["b", "c", "d"]]);
+        assert_eq!(graph.cliques(2).len(), graph.edge_count());
+        assert!(graph.cliques(4).is_empty());
+
+        let mut maximal = Vec::new();
+        graph.for_each_maximal_clique(|c| maximal.push(names(&graph, c)));
+        maximal.sort();
+        assert_eq!(
+            maximal,
+            [
+                vec!["a", "b", "c"],
+                vec!["b", "c", "d"],
+                vec!["d", "e"],
+                vec!["x", "y"]
+            ]
+        );
+
+        let mut complete = example();
+        complete.add_edge("a", "d");
+        assert_eq!(
+            names(&complete, &complete.maximum_clique()),
+            ["a", "b", "c", "d"]
+        );
+        assert!(Graph::<u8>::new().maximum_clique().is_empty());
+        let mut found = 0;
+        Graph::<u8>::new().for_each_maximal_clique(|_| found += 1);
+        assert_eq!(found, 0);
+    }
+}
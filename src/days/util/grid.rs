@@ -0,0 +1,356 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use super::parse::{complete, int, opt, pair, spaces, terminated};
+use crate::days::error::Error;
+
+/// A position on a grid, with `x` counting columns to the right and `y` rows down.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Coord {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Coord {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Coord { x, y }
+    }
+
+    pub fn offset(self, dx: i32, dy: i32) -> Coord {
+        Coord::new(self.x + dx, self.y + dy)
+    }
+
+    /// The neighbouring coordinate in `direction`.
+    pub fn step(self, direction: Direction) -> Coord {
+        let (dx, dy) = direction.delta();
+        self.offset(dx, dy)
+    }
+
+    /// The four orthogonal neighbours, in the order of [`Direction::ALL`].
+    pub fn neighbours(self) -> impl Iterator<Item = Coord> {
+        Direction::ALL.into_iter().map(move |d| self.step(d))
+    }
+
+    /// The eight neighbours including diagonals, clockwise from north.
+    pub fn neighbours8(self) -> impl Iterator<Item = Coord> {
+        [
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ]
+        .into_iter()
+        .map(move |(dx, dy)| self.offset(dx, dy))
+    }
+
+    pub fn manhattan(self, other: Coord) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+/// Written and parsed as `x,y`, the way puzzles list coordinates.
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl FromStr for Coord {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let comma = pair(",", opt(spaces()));
+        let (x, y) = complete(s, pair(terminated(int(), comma), int()))?;
+        Ok(Coord::new(x, y))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Every direction, clockwise from north.
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// The direction drawn as `^`, `>`, `v` or `<`.
+    pub fn from_arrow(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Direction::North),
+            '>' => Some(Direction::East),
+            'v' => Some(Direction::South),
+            '<' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    pub fn delta(self) -> (i32, i32) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::East => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+        }
+    }
+
+    pub fn clockwise(self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn anticlockwise(self) -> Direction {
+        self.clockwise().opposite()
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.clockwise().clockwise()
+    }
+}
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Parses a grid with one cell per character. `cell` returns `None` for
+    /// characters it doesn't expect, which fails with their line and column, as
+    /// do lines of differing lengths.
+    pub fn parse_with(
+        s: &str,
+        mut cell: impl FnMut(Coord, char) -> Option<T>,
+    ) -> Result<Self, Error> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for (y, line) in s.lines().enumerate() {
+            let mut columns = 0;
+            for (x, c) in line.chars().enumerate() {
+                let value = cell(Coord::new(x as i32, y as i32), c).ok_or_else(|| {
+                    Error::parse(line, format!("unexpected character '{}'", c))
+                        .at_line(y + 1)
+                        .at_column(x + 1)
+                })?;
+                cells.push(value);
+                columns += 1;
+            }
+            if *width.get_or_insert(columns) != columns {
+                return Err(Error::parse(
+                    line,
+                    format!("expected {} columns, found {}", width.unwrap(), columns),
+                )
+                .at_line(y + 1));
+            }
+            height += 1;
+        }
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        (0..self.width as i32).contains(&coord.x) && (0..self.height as i32).contains(&coord.y)
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        self.index_of(coord).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        self.index_of(coord).map(|i| &mut self.cells[i])
+    }
+
+    /// Every coordinate on the grid, row by row.
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.width * self.height)
+            .map(move |i| Coord::new((i % width) as i32, (i / width) as i32))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.coords().zip(&self.cells)
+    }
+
+    /// The coordinates of the cells that match `predicate`.
+    pub fn positions<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Coord> + 'a {
+        self.iter()
+            .filter(move |(_, t)| predicate(t))
+            .map(|(c, _)| c)
+    }
+
+    /// The orthogonal neighbours of `coord` that are on the grid.
+    pub fn neighbours(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        coord.neighbours().filter(|&c| self.contains(c))
+    }
+
+    /// The neighbours of `coord` that are on the grid, including diagonals.
+    pub fn neighbours8(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        coord.neighbours8().filter(|&c| self.contains(c))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn index_of(&self, coord: Coord) -> Option<usize> {
+        self.contains(coord)
+            .then(|| coord.y as usize * self.width + coord.x as usize)
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse_with(s, |_, c| Some(c))
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &T {
+        match self.get(coord) {
+            Some(cell) => cell,
+            None => panic!("{} is outside of the grid", coord),
+        }
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        match self.index_of(coord) {
+            Some(i) => &mut self.cells[i],
+            None => panic!("{} is outside of the grid", coord),
+        }
+    }
+}
+
+/// Draws the grid one row per line, e.g. to print it while debugging.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.chunks(self.width.max(1)) {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::error::ParseError;
+
+    #[test]
+    fn test_parse() {
+        let grid: Grid<char> = "ab\ncd\nef".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[Coord::new(1, 2)], 'f');
+        assert_eq!(grid.get(Coord::new(2, 0)), None);
+        assert_eq!(grid.get(Coord::new(0, -1)), None);
+        assert_eq!(
+            grid.positions(|&c| c == 'd').collect::<Vec<_>>(),
+            [Coord::new(1, 1)]
+        );
+        assert_eq!(grid.to_string(), "ab\ncd\nef\n");
+
+        let digits = Grid::parse_with("12\n3x", |_, c| c.to_digit(10));
+        assert!(matches!(
+            digits,
+            Err(Error::Parse(ParseError {
+                line: Some(2),
+                column: Some(2),
+                ..
+            }))
+        ));
+        assert!(matches!(
+            "ab\nc".parse::<Grid<char>>(),
+            Err(Error::Parse(ParseError { line: Some(2), .. }))
+        ));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 2, 0);
+        assert_eq!(
+            grid.neighbours(Coord::new(0, 0)).collect::<Vec<_>>(),
+            [Coord::new(1, 0), Coord::new(0, 1)]
+        );
+        assert_eq!(grid.neighbours8(Coord::new(1, 0)).count(), 5);
+        assert_eq!(Coord::new(1, 1).neighbours8().count(), 8);
+        assert_eq!(Coord::new(1, 1).manhattan(Coord::new(-2, 3)), 5);
+    }
+
+    #[test]
+    fn test_directions() {
+        let mut direction = Direction::North;
+        for expected in [
+            Direction::East,
+            Direction::South,
+            Direction::West,
+            Direction::North,
+        ] {
+            direction = direction.clockwise();
+            assert_eq!(direction, expected);
+        }
+        assert_eq!(Direction::East.anticlockwise(), Direction::North);
+        assert_eq!(Direction::West.opposite(), Direction::East);
+        assert_eq!(Direction::from_arrow('v'), Some(Direction::South));
+        assert_eq!(Coord::new(2, 2).step(Direction::West), Coord::new(1, 2));
+        assert_eq!("3, 4".parse::<Coord>().unwrap().to_string(), "3,4");
+    }
+}
@@ -0,0 +1,47 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::error::Error;
+
+pub mod graph;
+pub mod grid;
+pub mod number;
+pub mod parse;
+pub mod search;
+
+/// Parses the value of a puzzle parameter given on the command line.
+pub fn parse_param<T: FromStr>(name: &str, value: &str) -> Result<T, Error>
+where
+    T::Err: Display,
+{
+    value.parse().map_err(|e| {
+        Error::invalid_config(format!("invalid value '{}' for {}: {}", value, name, e))
+    })
+}
+
+/// Parses a size given as a puzzle parameter, which must be at least one.
+pub fn parse_size_param(name: &str, value: &str) -> Result<usize, Error> {
+    match parse_param(name, value)? {
+        0 => Err(Error::invalid_config(format!("{} must be positive", name))),
+        size => Ok(size),
+    }
+}
+
+/// Parses every line of `s`, tagging errors with the line they came from.
+pub fn parse_lines<T: FromStr<Err = Error>>(s: &str) -> Result<Vec<T>, Error> {
+    s.lines()
+        .enumerate()
+        .map(|(i, line)| line.parse().map_err(|e: Error| e.at_line(i + 1)))
+        .collect()
+}
+
+/// Parses each blank line separated block of `s`, tagging errors with their line.
+pub fn parse_blocks<T: FromStr<Err = Error>>(s: &str) -> Result<Vec<T>, Error> {
+    let mut line = 1;
+    s.split("\n\n")
+        .map(|block| {
+            let start = line;
+            line += block.matches('\n').count() + 2;
+            block.parse().map_err(|e: Error| e.at_line(start))
+        })
+        .collect()
+}
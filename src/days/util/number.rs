@@ -0,0 +1,338 @@
+use std::{
+    fmt,
+    mem::swap,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// The result of arithmetic that doesn't fit in an `i64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arithmetic overflow")
+    }
+}
+
+pub fn gcd(a: usize, b: usize) -> usize {
+    let mut r = (a, b);
+
+    while r.0 != 0 {
+        let q = r.1 / r.0;
+        swap(&mut r.0, &mut r.1);
+        r.0 -= q * r.1;
+    }
+
+    r.1
+}
+
+pub fn lcm(a: usize, b: usize) -> usize {
+    a * b / gcd(a, b)
+}
+
+/// Returns `(g, x, y)` where `g` is the non-negative gcd of `a` and `b`, and
+/// `a * x + b * y == g`, or `None` if any of them doesn't fit in an `i64`, as
+/// for a gcd of 2⁶³.
+pub fn extended_gcd(a: i64, b: i64) -> Option<(i64, i64, i64)> {
+    let (g, x, y) = extended_gcd_wide(a as i128, b as i128);
+    Some((g.try_into().ok()?, x.try_into().ok()?, y.try_into().ok()?))
+}
+
+fn extended_gcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1, 0);
+    let (mut y0, mut y1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    if r0 < 0 {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+/// The `x` in `0..|m|` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+/// `None` for a modulus of zero.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    if m == 0 {
+        return None;
+    }
+    let m = (m as i128).abs();
+    let (g, x, _) = extended_gcd_wide((a as i128).rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m) as i64)
+}
+
+/// Combines congruences `x ≡ r (mod m)` into a single `(r, m)`, with `r` in
+/// `0..m` and `m` the lcm of the moduli. The moduli needn't be coprime, in
+/// which case `None` is returned if the congruences contradict each other, as
+/// it is for a modulus of zero.
+///
+/// Panics if the combined modulus overflows, see [`checked_crt`].
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    checked_crt(congruences).unwrap_or_else(|e| panic!("{} in crt", e))
+}
+
+/// Like [`crt`], but failing with [`Overflow`] instead of panicking.
+pub fn checked_crt(
+    congruences: impl IntoIterator<Item = (i64, i64)>,
+) -> Result<Option<(i64, i64)>, Overflow> {
+    let (mut r, mut m) = (0i128, 1i128);
+    for (r2, m2) in congruences {
+        if m2 == 0 {
+            return Ok(None);
+        }
+        let (r2, m2) = ((r2 as i128).rem_euclid(m2 as i128), (m2 as i128).abs());
+        let (g, p, _) = extended_gcd_wide(m, m2);
+        if (r2 - r) % g != 0 {
+            return Ok(None);
+        }
+        // x = r + m * k, where m * k ≡ r2 - r (mod m2).
+        let lcm = m / g * m2;
+        let k = ((r2 - r) / g % (m2 / g)) * p % (m2 / g);
+        r = (r + m * k).rem_euclid(lcm);
+        m = lcm;
+        if m > i64::MAX as i128 {
+            return Err(Overflow);
+        }
+    }
+    Ok(Some((r as i64, m as i64)))
+}
+
+/// An exact fraction, always in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: i64,
+    denom: i64,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational::integer(0);
+    pub const ONE: Rational = Rational::integer(1);
+
+    /// Panics if `denom` is zero.
+    pub fn new(numer: i64, denom: i64) -> Self {
+        assert!(denom != 0, "zero denominator");
+        Self::reduce(numer as i128, denom as i128).expect("overflow in Rational::new")
+    }
+
+    pub const fn integer(n: i64) -> Self {
+        Rational { numer: n, denom: 1 }
+    }
+
+    pub fn numer(self) -> i64 {
+        self.numer
+    }
+
+    pub fn denom(self) -> i64 {
+        self.denom
+    }
+
+    /// The value as an integer, if it is one.
+    pub fn to_integer(self) -> Option<i64> {
+        (self.denom == 1).then_some(self.numer)
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numer == 0
+    }
+
+    pub fn checked_add(self, other: Rational) -> Option<Rational> {
+        let (a, b) = (self.wide(), other.wide());
+        Self::reduce(a.0 * b.1 + b.0 * a.1, a.1 * b.1)
+    }
+
+    pub fn checked_sub(self, other: Rational) -> Option<Rational> {
+        let (a, b) = (self.wide(), other.wide());
+        Self::reduce(a.0 * b.1 - b.0 * a.1, a.1 * b.1)
+    }
+
+    pub fn checked_mul(self, other: Rational) -> Option<Rational> {
+        let (a, b) = (self.wide(), other.wide());
+        Self::reduce(a.0 * b.0, a.1 * b.1)
+    }
+
+    /// `None` on overflow or division by zero.
+    pub fn checked_div(self, other: Rational) -> Option<Rational> {
+        let (a, b) = (self.wide(), other.wide());
+        Self::reduce(a.0 * b.1, a.1 * b.0)
+    }
+
+    fn wide(self) -> (i128, i128) {
+        (self.numer as i128, self.denom as i128)
+    }
+
+    fn reduce(numer: i128, denom: i128) -> Option<Rational> {
+        if denom == 0 {
+            return None;
+        }
+        let (g, _, _) = extended_gcd_wide(numer, denom);
+        let sign = denom.signum();
+        Some(Rational {
+            numer: (sign * numer / g).try_into().ok()?,
+            denom: (sign * denom / g).try_into().ok()?,
+        })
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Rational::integer(n)
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.denom {
+            1 => write!(f, "{}", self.numer),
+            _ => write!(f, "{}/{}", self.numer, self.denom),
+        }
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            numer: self
+                .numer
+                .checked_neg()
+                .expect("overflow negating rational"),
+            denom: self.denom,
+        }
+    }
+}
+
+macro_rules! rational_op {
+    ($trait:ident, $method:ident, $checked:ident) => {
+        impl $trait for Rational {
+            type Output = Rational;
+
+            fn $method(self, other: Rational) -> Rational {
+                match self.$checked(other) {
+                    Some(result) => result,
+                    None => panic!("overflow in {} {} {}", self, stringify!($method), other),
+                }
+            }
+        }
+    };
+}
+
+rational_op!(Add, add, checked_add);
+rational_op!(Sub, sub, checked_sub);
+rational_op!(Mul, mul, checked_mul);
+rational_op!(Div, div, checked_div);
+
+/// Solves `a * x = b` exactly, returning `None` if `a` is singular so there's
+/// no unique solution.
+///
+/// Panics if an intermediate value overflows, see [`checked_solve_linear`].
+pub fn solve_linear<const N: usize>(a: [[i64; N]; N], b: [i64; N]) -> Option<[Rational; N]> {
+    checked_solve_linear(a, b).unwrap_or_else(|e| panic!("{} in solve_linear", e))
+}
+
+/// Like [`solve_linear`], but failing with [`Overflow`] instead of panicking.
+pub fn checked_solve_linear<const N: usize>(
+    a: [[i64; N]; N],
+    b: [i64; N],
+) -> Result<Option<[Rational; N]>, Overflow> {
+    // Gaussian elimination on the augmented matrix.
+    let mut rows: [Vec<Rational>; N] = std::array::from_fn(|i| {
+        a[i].iter()
+            .chain([&b[i]])
+            .map(|&n| Rational::integer(n))
+            .collect()
+    });
+    for col in 0..N {
+        let Some(pivot) = (col..N).find(|&r| !rows[r][col].is_zero()) else {
+            return Ok(None);
+        };
+        rows.swap(col, pivot);
+        let pivot = rows[col].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r == col || row[col].is_zero() {
+                continue;
+            }
+            let factor = row[col].checked_div(pivot[col]).ok_or(Overflow)?;
+            for (cell, &p) in row.iter_mut().zip(&pivot).skip(col) {
+                *cell = cell
+                    .checked_sub(p.checked_mul(factor).ok_or(Overflow)?)
+                    .ok_or(Overflow)?;
+            }
+        }
+    }
+    let mut x = [Rational::ZERO; N];
+    for (i, row) in rows.iter().enumerate() {
+        x[i] = row[N].checked_div(row[i]).ok_or(Overflow)?;
+    }
+    Ok(Some(x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(lcm(4, 6), 12);
+        let (g, x, y) = extended_gcd(240, -46).unwrap();
+        assert_eq!(g, 2);
+        assert_eq!(240 * x - 46 * y, 2);
+        assert_eq!(extended_gcd(i64::MIN, 0), None);
+        let (g, x, y) = extended_gcd(i64::MIN, 6).unwrap();
+        assert_eq!(g, 2);
+        assert_eq!(i64::MIN as i128 * x as i128 + 6 * y as i128, 2);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(4, 10), None);
+        assert_eq!(mod_inverse(3, 0), None);
+        let inverse = mod_inverse(3, i64::MIN).unwrap();
+        assert_eq!((3 * inverse as i128).rem_euclid(1 << 63), 1);
+        assert_eq!(mod_inverse(2, i64::MIN), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(crt([(1, 3), (0, 0)]), None);
+        assert_eq!(checked_crt([(5, i64::MIN)]), Err(Overflow));
+        let big = i64::MAX / 2;
+        assert_eq!(checked_crt([(0, big), (0, big - 2)]), Err(Overflow));
+    }
+
+    #[test]
+    fn test_rational() {
+        let half = Rational::new(2, 4);
+        assert_eq!((half.numer(), half.denom()), (1, 2));
+        assert_eq!(Rational::new(3, -6), -half);
+        assert_eq!(half + Rational::new(1, 3), Rational::new(5, 6));
+        assert_eq!((half * Rational::integer(4)).to_integer(), Some(2));
+        assert_eq!((half / Rational::new(1, 3)).to_string(), "3/2");
+        assert_eq!(half.checked_div(Rational::ZERO), None);
+        assert_eq!(Rational::integer(i64::MAX).checked_add(Rational::ONE), None);
+    }
+
+    #[test]
+    fn test_solve_linear() {
+        let x = solve_linear([[94, 22], [34, 67]], [8400, 5400]).unwrap();
+        assert_eq!(x.map(Rational::to_integer), [Some(80), Some(40)]);
+        let x = solve_linear([[0, 2, 1], [1, 0, 0], [1, 1, 1]], [3, 1, 3]).unwrap();
+        assert_eq!(x.map(|r| r.to_string()), ["1", "1", "1"]);
+        assert_eq!(solve_linear([[1, 2], [2, 4]], [3, 6]), None);
+        let x = solve_linear([[2, 0], [0, 3]], [1, 1]).unwrap();
+        assert_eq!(x, [Rational::new(1, 2), Rational::new(1, 3)]);
+        assert_eq!(
+            checked_solve_linear([[1, 0], [0, i64::MAX]], [1, i64::MIN]),
+            Ok(Some([Rational::ONE, Rational::new(i64::MIN, i64::MAX)]))
+        );
+    }
+}
@@ -0,0 +1,459 @@
+//! Parser combinators for puzzle inputs.
+//!
+//! A parser is anything implementing [`Parser`]: closures taking an [`Input`],
+//! and string literals, which match themselves. Parsers are built up from the
+//! functions here and run with [`complete`], which reports failures with the
+//! line and column they happened at.
+//!
+//! ```
+//! use aoc24::days::util::parse::{complete, int, lines, pair, preceded, word};
+//!
+//! let button = preceded("Button ", pair(word(), preceded(": X+", int::<i64>())));
+//! let buttons = complete("Button A: X+94\nButton B: X+22\n", lines(button));
+//! assert_eq!(buttons.unwrap(), [("A", 94), ("B", 22)]);
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::days::error::Error;
+
+/// The part of the text that's left to parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Input<'a> {
+    source: &'a str,
+    offset: usize,
+}
+
+/// Why a parser didn't match, and where. Turned into an [`Error`] with a line
+/// and column by [`complete`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    offset: usize,
+    message: String,
+}
+
+pub type Parsed<'a, T> = Result<(T, Input<'a>), Failure>;
+
+pub trait Parser<'a, T> {
+    fn parse(&self, input: Input<'a>) -> Parsed<'a, T>;
+}
+
+impl<'a, T, F: Fn(Input<'a>) -> Parsed<'a, T>> Parser<'a, T> for F {
+    fn parse(&self, input: Input<'a>) -> Parsed<'a, T> {
+        self(input)
+    }
+}
+
+/// Matches the string itself.
+impl<'a> Parser<'a, &'a str> for &str {
+    fn parse(&self, input: Input<'a>) -> Parsed<'a, &'a str> {
+        if input.rest().starts_with(*self) {
+            Ok(input.split(self.len()))
+        } else if *self == "\n" {
+            Err(input.fail("expected the end of the line"))
+        } else {
+            Err(input.fail(format!("expected '{}'", self.escape_debug())))
+        }
+    }
+}
+
+impl<'a> Input<'a> {
+    pub fn rest(&self) -> &'a str {
+        &self.source[self.offset..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest().is_empty()
+    }
+
+    /// A failure at the current position.
+    pub fn fail(&self, message: impl Display) -> Failure {
+        Failure {
+            offset: self.offset,
+            message: message.to_string(),
+        }
+    }
+
+    /// Takes the next `len` bytes of the input.
+    pub fn split(self, len: usize) -> (&'a str, Input<'a>) {
+        let taken = &self.rest()[..len];
+        let rest = Input {
+            offset: self.offset + len,
+            ..self
+        };
+        (taken, rest)
+    }
+
+    /// Takes the longest prefix whose characters match `predicate`.
+    pub fn split_while(self, predicate: impl Fn(char) -> bool) -> (&'a str, Input<'a>) {
+        let len = self
+            .rest()
+            .find(|c| !predicate(c))
+            .unwrap_or(self.rest().len());
+        self.split(len)
+    }
+}
+
+impl Failure {
+    fn into_error(self, source: &str) -> Error {
+        let before = &source[..self.offset];
+        let start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line = before.matches('\n').count() + 1;
+        let column = before[start..].chars().count() + 1;
+        let text = source[start..].lines().next().unwrap_or("");
+        Error::parse(text, self.message)
+            .at_line(line)
+            .at_column(column)
+    }
+}
+
+/// Runs `parser` on all of `s`, allowing only whitespace to be left over.
+pub fn complete<'a, T>(s: &'a str, parser: impl Parser<'a, T>) -> Result<T, Error> {
+    let input = Input {
+        source: s,
+        offset: 0,
+    };
+    parser
+        .parse(input)
+        .and_then(|(value, rest)| {
+            let (_, rest) = rest.split_while(char::is_whitespace);
+            if rest.is_empty() {
+                Ok(value)
+            } else {
+                let unexpected = rest.rest().lines().next().unwrap_or_default();
+                Err(rest.fail(format!("unexpected '{}'", unexpected)))
+            }
+        })
+        .map_err(|failure| failure.into_error(s))
+}
+
+fn number<'a, T>(input: Input<'a>, signed: bool) -> Parsed<'a, T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let sign = usize::from(signed && input.rest().starts_with(['-', '+']));
+    let (_, after_sign) = input.split(sign);
+    let (digits, _) = after_sign.split_while(|c| c.is_ascii_digit());
+    if digits.is_empty() {
+        return Err(after_sign.fail("expected a number"));
+    }
+    let (text, rest) = input.split(sign + digits.len());
+    let value = text.parse().map_err(|e| input.fail(e))?;
+    Ok((value, rest))
+}
+
+/// An unsigned integer, made of digits only.
+pub fn uint<'a, T>() -> impl Fn(Input<'a>) -> Parsed<'a, T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    |input| number(input, false)
+}
+
+/// An integer with an optional `-` or `+` sign.
+pub fn int<'a, T>() -> impl Fn(Input<'a>) -> Parsed<'a, T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    |input| number(input, true)
+}
+
+/// A single decimal digit.
+pub fn digit<'a>() -> impl Fn(Input<'a>) -> Parsed<'a, u32> {
+    |input| match input.rest().chars().next().and_then(|c| c.to_digit(10)) {
+        Some(digit) => Ok((digit, input.split(1).1)),
+        None => Err(input.fail("expected a digit")),
+    }
+}
+
+pub fn any_char<'a>() -> impl Fn(Input<'a>) -> Parsed<'a, char> {
+    |input| match input.rest().chars().next() {
+        Some(c) => Ok((c, input.split(c.len_utf8()).1)),
+        None => Err(input.fail("unexpected end of input")),
+    }
+}
+
+/// One or more letters, digits or underscores.
+pub fn word<'a>() -> impl Fn(Input<'a>) -> Parsed<'a, &'a str> {
+    |input| match input.split_while(|c| c.is_alphanumeric() || c == '_') {
+        ("", _) => Err(input.fail("expected a word")),
+        found => Ok(found),
+    }
+}
+
+/// One or more spaces or tabs.
+pub fn spaces<'a>() -> impl Fn(Input<'a>) -> Parsed<'a, ()> {
+    |input| match input.split_while(|c| c == ' ' || c == '\t') {
+        ("", _) => Err(input.fail("expected a space")),
+        (_, rest) => Ok(((), rest)),
+    }
+}
+
+pub fn map<'a, T, U>(
+    parser: impl Parser<'a, T>,
+    f: impl Fn(T) -> U,
+) -> impl Fn(Input<'a>) -> Parsed<'a, U> {
+    move |input| parser.parse(input).map(|(value, rest)| (f(value), rest))
+}
+
+/// Converts the parsed value with `f`, failing where the value started if
+/// that fails.
+pub fn try_map<'a, T, U, E: Display>(
+    parser: impl Parser<'a, T>,
+    f: impl Fn(T) -> Result<U, E>,
+) -> impl Fn(Input<'a>) -> Parsed<'a, U> {
+    move |input| {
+        let (value, rest) = parser.parse(input)?;
+        Ok((f(value).map_err(|e| input.fail(e))?, rest))
+    }
+}
+
+pub fn pair<'a, A, B>(
+    first: impl Parser<'a, A>,
+    second: impl Parser<'a, B>,
+) -> impl Fn(Input<'a>) -> Parsed<'a, (A, B)> {
+    move |input| {
+        let (a, rest) = first.parse(input)?;
+        let (b, rest) = second.parse(rest)?;
+        Ok(((a, b), rest))
+    }
+}
+
+/// Parses `prefix` then `parser`, keeping the latter.
+pub fn preceded<'a, P, T>(
+    prefix: impl Parser<'a, P>,
+    parser: impl Parser<'a, T>,
+) -> impl Fn(Input<'a>) -> Parsed<'a, T> {
+    map(pair(prefix, parser), |(_, value)| value)
+}
+
+/// Parses `parser` then `suffix`, keeping the former.
+pub fn terminated<'a, T, S>(
+    parser: impl Parser<'a, T>,
+    suffix: impl Parser<'a, S>,
+) -> impl Fn(Input<'a>) -> Parsed<'a, T> {
+    map(pair(parser, suffix), |(value, _)| value)
+}
+
+pub fn delimited<'a, P, T, S>(
+    prefix: impl Parser<'a, P>,
+    parser: impl Parser<'a, T>,
+    suffix: impl Parser<'a, S>,
+) -> impl Fn(Input<'a>) -> Parsed<'a, T> {
+    preceded(prefix, terminated(parser, suffix))
+}
+
+pub fn opt<'a, T>(parser: impl Parser<'a, T>) -> impl Fn(Input<'a>) -> Parsed<'a, Option<T>> {
+    move |input| match parser.parse(input) {
+        Ok((value, rest)) => Ok((Some(value), rest)),
+        Err(_) => Ok((None, input)),
+    }
+}
+
+/// Tries `first`, then `second`. When both fail, the failure that got furthest
+/// is the one reported.
+pub fn or<'a, T>(
+    first: impl Parser<'a, T>,
+    second: impl Parser<'a, T>,
+) -> impl Fn(Input<'a>) -> Parsed<'a, T> {
+    move |input| {
+        first.parse(input).or_else(|a| {
+            second
+                .parse(input)
+                .map_err(|b| if a.offset > b.offset { a } else { b })
+        })
+    }
+}
+
+/// Repeats `parser` until it fails, stopping quietly if it fails straight away
+/// but propagating the failure if it fails part way through.
+pub fn many<'a, T>(parser: impl Parser<'a, T>) -> impl Fn(Input<'a>) -> Parsed<'a, Vec<T>> {
+    move |input| repeat(&parser, input, Vec::new())
+}
+
+/// Like [`many`], but failing as `parser` does if it doesn't match at least once.
+pub fn many1<'a, T>(parser: impl Parser<'a, T>) -> impl Fn(Input<'a>) -> Parsed<'a, Vec<T>> {
+    move |input| {
+        let (first, rest) = parser.parse(input)?;
+        repeat(&parser, rest, vec![first])
+    }
+}
+
+fn repeat<'a, T>(
+    parser: &impl Parser<'a, T>,
+    mut input: Input<'a>,
+    mut values: Vec<T>,
+) -> Parsed<'a, Vec<T>> {
+    loop {
+        match parser.parse(input) {
+            Ok((value, rest)) if rest != input => {
+                values.push(value);
+                input = rest;
+            }
+            Err(failure) if failure.offset > input.offset => return Err(failure),
+            _ => return Ok((values, input)),
+        }
+    }
+}
+
+/// One or more of `item`, separated by `separator`. Like [`many`], a separator
+/// not followed by an item ends the list, unless the item fails part way through.
+pub fn separated<'a, T, S>(
+    item: impl Parser<'a, T>,
+    separator: impl Parser<'a, S>,
+) -> impl Fn(Input<'a>) -> Parsed<'a, Vec<T>> {
+    move |input| {
+        let (first, mut input) = item.parse(input)?;
+        let mut items = vec![first];
+        while let Ok((_, after)) = separator.parse(input) {
+            match item.parse(after) {
+                Ok((value, rest)) => {
+                    items.push(value);
+                    input = rest;
+                }
+                Err(failure) if failure.offset > after.offset => return Err(failure),
+                Err(_) => break,
+            }
+        }
+        Ok((items, input))
+    }
+}
+
+/// One `item` per line, up to a blank line or the end of the input.
+pub fn lines<'a, T>(item: impl Parser<'a, T>) -> impl Fn(Input<'a>) -> Parsed<'a, Vec<T>> {
+    separated(item, "\n")
+}
+
+/// Blocks of lines separated by blank lines, each parsed by `item`.
+pub fn blocks<'a, T>(item: impl Parser<'a, T>) -> impl Fn(Input<'a>) -> Parsed<'a, Vec<T>> {
+    separated(item, "\n\n")
+}
+
+/// A `key: value` pair.
+pub fn key_value<'a, K, V>(
+    key: impl Parser<'a, K>,
+    value: impl Parser<'a, V>,
+) -> impl Fn(Input<'a>) -> Parsed<'a, (K, V)> {
+    pair(terminated(key, ": "), value)
+}
+
+/// The value of the field called `name`, written `name: value`.
+pub fn field<'a, V>(
+    name: &'static str,
+    value: impl Parser<'a, V>,
+) -> impl Fn(Input<'a>) -> Parsed<'a, V> {
+    map(key_value(name, value), |(_, value)| value)
+}
+
+/// Every match of `parser` in the input, skipping over anything in between.
+pub fn scan<'a, T>(parser: impl Parser<'a, T>) -> impl Fn(Input<'a>) -> Parsed<'a, Vec<T>> {
+    move |mut input| {
+        let mut found = Vec::new();
+        while !input.is_empty() {
+            match parser.parse(input) {
+                Ok((value, rest)) if rest != input => {
+                    found.push(value);
+                    input = rest;
+                }
+                _ => input = any_char()(input)?.1,
+            }
+        }
+        Ok((found, input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::error::ParseError;
+
+    fn position(
+        result: Result<impl std::fmt::Debug, Error>,
+    ) -> (Option<usize>, Option<usize>, String) {
+        match result {
+            Err(Error::Parse(ParseError {
+                line,
+                column,
+                message,
+                ..
+            })) => (line, column, message),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(complete("42", uint::<u8>()), Ok(42));
+        assert_eq!(complete("-42", int::<i32>()), Ok(-42));
+        assert_eq!(complete("+7\n", int::<i32>()), Ok(7));
+        assert_eq!(
+            position(complete("-1", uint::<u8>())),
+            (Some(1), Some(1), "expected a number".to_owned())
+        );
+        assert_eq!(
+            position(complete("300", uint::<u8>())),
+            (
+                Some(1),
+                Some(1),
+                "number too large to fit in target type".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        let report = separated(uint::<u32>(), spaces());
+        assert_eq!(
+            complete("1 2\n3  4\n", lines(&report)),
+            Ok(vec![vec![1, 2], vec![3, 4]])
+        );
+        assert_eq!(
+            position(complete("1 2\n3 x", lines(&report))),
+            (Some(2), Some(3), "unexpected 'x'".to_owned())
+        );
+
+        let block = lines(key_value(word(), uint::<u32>()));
+        assert_eq!(
+            complete("a: 1\nb: 2\n\nc: 3\n", blocks(&block)),
+            Ok(vec![vec![("a", 1), ("b", 2)], vec![("c", 3)]])
+        );
+        assert_eq!(
+            position(complete("a: 1\n\nb: 2\nc: x\n", blocks(&block))),
+            (Some(4), Some(4), "expected a number".to_owned())
+        );
+        assert_eq!(complete("12345", many(digit())), Ok(vec![1, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_fields() {
+        let register = |name| field(name, uint::<u64>());
+        let computer = pair(
+            lines(or(register("Register A"), register("Register B"))),
+            preceded("\n\n", field("Program", separated(uint::<u8>(), ","))),
+        );
+        assert_eq!(
+            complete("Register A: 1\nRegister B: 2\n\nProgram: 0,3\n", &computer),
+            Ok((vec![1, 2], vec![0, 3]))
+        );
+        assert_eq!(
+            position(complete("Register C: 1", &computer)),
+            (Some(1), Some(1), "expected 'Register B'".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_scan() {
+        let mul = delimited(
+            "mul(",
+            pair(terminated(uint::<u32>(), ","), uint::<u32>()),
+            ")",
+        );
+        assert_eq!(
+            complete("xmul(2,4)%mul(3,x)mul(mul(5,5)", scan(mul)),
+            Ok(vec![(2, 4), (5, 5)])
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+/// What a search found: the cost of reaching every node it visited, and the
+/// cheapest ways of getting there.
+#[derive(Debug, Clone)]
+pub struct Search<N> {
+    start: N,
+    costs: HashMap<N, usize>,
+    /// Every node that leads to the key on one of its cheapest paths.
+    parents: HashMap<N, Vec<N>>,
+    /// The goals reached at the lowest cost, in the order they were found.
+    goals: Vec<N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new(start: N) -> Self {
+        Search {
+            costs: HashMap::from([(start.clone(), 0)]),
+            start,
+            parents: HashMap::new(),
+            goals: Vec::new(),
+        }
+    }
+
+    /// Records that `node` can be reached from `parent` at `cost`, returning
+    /// whether that's cheaper than any way found before. The start never gets a
+    /// parent, even when a zero cost cycle leads back to it.
+    fn relax(&mut self, parent: &N, node: N, cost: usize) -> bool {
+        if node == self.start {
+            return false;
+        }
+        match self.costs.entry(node.clone()) {
+            Entry::Occupied(mut known) if cost < *known.get() => {
+                known.insert(cost);
+                self.parents.insert(node, vec![parent.clone()]);
+                true
+            }
+            Entry::Occupied(known) => {
+                if cost == *known.get() {
+                    self.parents.entry(node).or_default().push(parent.clone());
+                }
+                false
+            }
+            Entry::Vacant(unknown) => {
+                unknown.insert(cost);
+                self.parents.insert(node, vec![parent.clone()]);
+                true
+            }
+        }
+    }
+
+    /// Whether the search can stop before expanding a node estimated at `cost`.
+    fn is_done(&self, cost: usize) -> bool {
+        self.goal_cost().is_some_and(|best| cost > best)
+    }
+
+    /// The cost of the cheapest path to `node`, if it was reached.
+    pub fn cost(&self, node: &N) -> Option<usize> {
+        self.costs.get(node).copied()
+    }
+
+    /// The cost of every node that was reached. Without a goal, that's every
+    /// node reachable from the start.
+    pub fn costs(&self) -> &HashMap<N, usize> {
+        &self.costs
+    }
+
+    pub fn into_costs(self) -> HashMap<N, usize> {
+        self.costs
+    }
+
+    /// The first goal that was reached.
+    pub fn goal(&self) -> Option<&N> {
+        self.goals.first()
+    }
+
+    pub fn goal_cost(&self) -> Option<usize> {
+        self.goal().and_then(|goal| self.cost(goal))
+    }
+
+    /// One of the cheapest paths from the start to the goal, including both.
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal()?)
+    }
+
+    /// One of the cheapest paths from the start to `node`, including both.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.costs.get(node)?;
+        let mut path = vec![node.clone()];
+        while path.last() != Some(&self.start) {
+            let parent = self.parents.get(path.last()?).and_then(|p| p.first())?;
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every node on any of the cheapest paths to any of the goals reached at
+    /// the lowest cost.
+    pub fn on_shortest_paths(&self) -> HashSet<N> {
+        let mut seen: HashSet<N> = self.goals.iter().cloned().collect();
+        let mut stack = self.goals.clone();
+        while let Some(node) = stack.pop() {
+            for parent in self.parents.get(&node).into_iter().flatten() {
+                if seen.insert(parent.clone()) {
+                    stack.push(parent.clone());
+                }
+            }
+        }
+        seen
+    }
+}
+
+/// Breadth first search from `start`, where every step costs 1. Stops once all
+/// of the nearest nodes that satisfy `is_goal` are found; pass `|_| false` to
+/// visit everything that can be reached.
+pub fn bfs<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(start.clone());
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((node, cost)) = queue.pop_front() {
+        if search.is_done(cost) {
+            break;
+        }
+        if is_goal(&node) {
+            search.goals.push(node);
+            continue;
+        }
+        for next in successors(&node) {
+            if search.relax(&node, next.clone(), cost + 1) {
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+    search
+}
+
+/// Cheapest first search from `start`, where `successors` gives each neighbour
+/// along with the cost of moving to it.
+pub fn dijkstra<N, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    astar(start, successors, |_| 0, is_goal)
+}
+
+/// Like [`dijkstra`], but guided towards the goal by `heuristic`, which must
+/// never overestimate the remaining cost, nor drop by more than the cost of a
+/// step.
+pub fn astar<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> usize,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut search = Search::new(start.clone());
+    let mut expanded = HashSet::new();
+    // Nodes are kept out of the heap so that they don't need to be ordered.
+    let mut nodes = vec![start.clone()];
+    let mut open = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+    while let Some(Reverse((estimate, cost, index))) = open.pop() {
+        if search.is_done(estimate) {
+            break;
+        }
+        let node = nodes[index].clone();
+        if cost > search.costs[&node] || !expanded.insert(node.clone()) {
+            continue;
+        }
+        if is_goal(&node) {
+            search.goals.push(node);
+            continue;
+        }
+        for (next, step) in successors(&node) {
+            let cost = cost + step;
+            if search.relax(&node, next.clone(), cost) {
+                open.push(Reverse((cost + heuristic(&next), cost, nodes.len())));
+                nodes.push(next);
+            }
+        }
+    }
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A diamond from 0 to 3 through 1 and 2, with a costly shortcut and a
+    /// dead end at 4.
+    fn diamond(node: &u32) -> Vec<(u32, usize)> {
+        match node {
+            0 => vec![(1, 1), (2, 1), (3, 5)],
+            1 => vec![(3, 2), (4, 1)],
+            2 => vec![(3, 2)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let search = dijkstra(0, diamond, |&n| n == 3);
+        assert_eq!(search.goal_cost(), Some(3));
+        assert_eq!(search.path(), Some(vec![0, 1, 3]));
+        assert_eq!(search.on_shortest_paths(), HashSet::from([0, 1, 2, 3]));
+        assert_eq!(search.path_to(&4), Some(vec![0, 1, 4]));
+
+        let search = dijkstra(0, diamond, |_| false);
+        assert_eq!(search.costs().len(), 5);
+        assert_eq!(search.goal(), None);
+        assert_eq!(search.on_shortest_paths(), HashSet::new());
+    }
+
+    #[test]
+    fn test_zero_cost_cycle() {
+        // 0 and 1 lead to each other for free, and 1 leads on to 2.
+        let successors = |&n: &u32| match n {
+            0 => vec![(1, 0)],
+            1 => vec![(0, 0), (2, 1)],
+            _ => vec![],
+        };
+        let search = dijkstra(0, successors, |&n| n == 2);
+        assert_eq!(search.path(), Some(vec![0, 1, 2]));
+        assert_eq!(search.path_to(&0), Some(vec![0]));
+        assert_eq!(search.on_shortest_paths(), HashSet::from([0, 1, 2]));
+    }
+
+    #[test]
+    fn test_bfs() {
+        let neighbours = |&n: &i32| [n - 1, n + 1].into_iter().filter(|n| (0..10).contains(n));
+        let search = bfs(5, neighbours, |&n| n == 0);
+        assert_eq!(search.goal_cost(), Some(5));
+        assert_eq!(search.path(), Some(vec![5, 4, 3, 2, 1, 0]));
+        assert_eq!(search.cost(&9), Some(4));
+        assert_eq!(bfs(5, neighbours, |&n| n == 10).goal(), None);
+    }
+
+    #[test]
+    fn test_astar() {
+        let target = (3i32, 3i32);
+        let successors = |&(x, y): &(i32, i32)| {
+            [(x + 1, y), (x, y + 1)]
+                .into_iter()
+                .filter(|&(x, y)| x <= 3 && y <= 3 && (x, y) != (1, 1))
+                .map(|n| (n, 1))
+        };
+        let heuristic = |&(x, y): &(i32, i32)| (target.0 - x + target.1 - y) as usize;
+        let search = astar((0, 0), successors, heuristic, |&n| n == target);
+        assert_eq!(search.goal_cost(), Some(6));
+        assert_eq!(search.path().map(|p| p.len()), Some(7));
+        // Of the 16 cells only the one that's blocked is off every shortest path.
+        assert_eq!(search.on_shortest_paths().len(), 15);
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    fs::{create_dir_all, write},
+    io::{self, Write},
+    path::Path,
+    process::{Command, Stdio},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use crate::{days::error::Error, runner::input_path};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const USER_AGENT: &str = concat!("aoc24/", env!("CARGO_PKG_VERSION"));
+
+/// Downloads puzzle inputs with `curl`, leaving at least `interval` between requests.
+pub struct Fetcher {
+    base_url: String,
+    session: String,
+    interval: Duration,
+    last_request: Option<Instant>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already on disk, so nothing was requested.
+    Present,
+    Downloaded,
+}
+
+impl Fetcher {
+    pub fn new(base_url: &str, session: &str, interval: Duration) -> Self {
+        Fetcher {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.to_owned(),
+            interval,
+            last_request: None,
+        }
+    }
+
+    pub fn url(&self, year: i32, day: i32) -> String {
+        format!("{}/{}/day/{}/input", self.base_url, year, day)
+    }
+
+    pub fn fetch(&mut self, year: i32, day: i32) -> Result<String, Error> {
+        if let Some(last) = self.last_request {
+            sleep(self.interval.saturating_sub(last.elapsed()));
+        }
+        self.last_request = Some(Instant::now());
+
+        let url = self.url(year, day);
+        let curl_error =
+            |e: io::Error| io::Error::new(e.kind(), format!("could not run curl: {}", e));
+        // The cookie goes through stdin, since other users can see the arguments.
+        let mut curl = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--config", "-"])
+            .args(["--user-agent", USER_AGENT])
+            .arg(&url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(curl_error)?;
+        let config = cookie_config(&self.session);
+        let written = curl
+            .stdin
+            .take()
+            .map(|mut stdin| stdin.write_all(config.as_bytes()));
+        let output = curl.wait_with_output().map_err(curl_error)?;
+        written.transpose().map_err(curl_error)?;
+        if !output.status.success() {
+            return Err(fetch_error(format!(
+                "{}: {}",
+                url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let input = String::from_utf8(output.stdout)
+            .map_err(|_| fetch_error(format!("{}: response is not UTF-8", url)))?;
+        validate_input(&input).map_err(|e| fetch_error(format!("{}: {}", url, e)))?;
+        Ok(input)
+    }
+}
+
+/// A curl config file that sends the session cookie.
+fn cookie_config(session: &str) -> String {
+    let quoted = session.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("cookie = \"session={}\"\n", quoted)
+}
+
+/// Rejects responses that are clearly not a puzzle input, such as the login page
+/// served for an expired session.
+pub fn validate_input(input: &str) -> Result<(), &'static str> {
+    let start = input.trim_start();
+    if start.is_empty() {
+        Err("response is empty")
+    } else if start.starts_with('<') {
+        Err("got an HTML page instead of an input, is the session cookie valid?")
+    } else if start.starts_with("Puzzle inputs differ by user") {
+        Err("not logged in, is the session cookie valid?")
+    } else {
+        Ok(())
+    }
+}
+
+/// Downloads the inputs that are missing from `dir`, stopping at the first
+/// failure rather than repeating a request that is likely to fail again.
+pub fn fetch_missing(
+    fetcher: &mut Fetcher,
+    dir: &Path,
+    year: i32,
+    days: &[i32],
+) -> Vec<(i32, Result<Fetched, Error>)> {
+    let mut results = Vec::new();
+    for day in days {
+        let path = input_path(dir, year, *day);
+        let result = if path.exists() {
+            Ok(Fetched::Present)
+        } else {
+            fetcher.fetch(year, *day).and_then(|input| {
+                create_dir_all(dir.join(year.to_string()))?;
+                write(&path, input)?;
+                Ok(Fetched::Downloaded)
+            })
+        };
+        let failed = result.is_err();
+        results.push((*day, result));
+        if failed {
+            break;
+        }
+    }
+    results
+}
+
+fn fetch_error(message: String) -> Error {
+    io::Error::new(io::ErrorKind::InvalidData, message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        fs::{read_to_string, remove_dir_all},
+        io::{BufRead, BufReader},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::*;
+
+    /// Serves one canned response per connection, returning the base URL and the
+    /// request lines that were received.
+    fn serve(responses: Vec<(u16, &'static str)>) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut lines = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    lines.push(line.trim().to_owned());
+                }
+                let cookie = lines.iter().find(|l| l.starts_with("Cookie:")).cloned();
+                requests.push(format!("{} {}", lines[0], cookie.unwrap_or_default()));
+                write!(
+                    stream,
+                    "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+            requests
+        });
+        (base_url, server)
+    }
+
+    #[test]
+    fn test_cookie_config() {
+        assert_eq!(cookie_config("abc"), "cookie = \"session=abc\"\n");
+        assert_eq!(
+            cookie_config(r#"a"b\c"#),
+            "cookie = \"session=a\\\"b\\\\c\"\n"
+        );
+    }
+
+    #[test]
+    fn test_validate_input() {
+        assert_eq!(validate_input("3   4\n"), Ok(()));
+        assert!(validate_input("").is_err());
+        assert!(validate_input("\n<!DOCTYPE html>\n<html>").is_err());
+        assert!(validate_input("Puzzle inputs differ by user.  Please log in.").is_err());
+    }
+
+    #[test]
+    fn test_fetch_missing() {
+        let dir = env::temp_dir().join(format!("aoc24-fetch-{}", std::process::id()));
+        let _ = remove_dir_all(&dir);
+        create_dir_all(dir.join("2024")).unwrap();
+        write(input_path(&dir, 2024, 1), "kept\n").unwrap();
+
+        let (base_url, server) = serve(vec![(200, "1 2\n"), (200, "<html>log in</html>")]);
+        let mut fetcher = Fetcher::new(&(base_url + "/"), "abc", Duration::ZERO);
+        let results = fetch_missing(&mut fetcher, &dir, 2024, &[1, 2, 3, 4]);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], (1, Ok(Fetched::Present)));
+        assert_eq!(results[1], (2, Ok(Fetched::Downloaded)));
+        assert_eq!(results[2].1.as_ref().unwrap_err().kind(), "io");
+        assert_eq!(
+            server.join().unwrap(),
+            [
+                "GET /2024/day/2/input HTTP/1.1 Cookie: session=abc",
+                "GET /2024/day/3/input HTTP/1.1 Cookie: session=abc",
+            ]
+        );
+        assert_eq!(read_to_string(input_path(&dir, 2024, 1)).unwrap(), "kept\n");
+        assert_eq!(read_to_string(input_path(&dir, 2024, 2)).unwrap(), "1 2\n");
+        assert!(!input_path(&dir, 2024, 3).exists());
+
+        remove_dir_all(&dir).unwrap();
+    }
+}
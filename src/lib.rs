@@ -0,0 +1,27 @@
+//! Solutions to Advent of Code 2024, along with the runner used by the `aoc24` binary.
+//!
+//! ```
+//! use aoc24::{registry, Program};
+//!
+//! let days = registry();
+//! let result = days[&(2024, 1)].run("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n".to_owned());
+//! assert_eq!(result.unwrap().part1, "11");
+//! ```
+
+pub mod answers;
+pub mod bench;
+pub mod days;
+pub mod fetch;
+pub mod memory;
+pub mod output;
+pub mod runner;
+pub mod scaffold;
+pub mod watch;
+
+pub use days::{
+    day::{
+        Context, Day, DayResult, Example, Part, Program, Progress, Registration, Reporter, Solver,
+    },
+    error::{Error, ParseError},
+    registry, Registry, DAYS,
+};
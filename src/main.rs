@@ -1,63 +1,475 @@
-use std::{collections::HashMap, fmt::Debug, fs::read_to_string, process::exit};
+use std::{
+    env,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    process::{self, exit},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use structopt::StructOpt;
+use structopt::{
+    clap::{self, AppSettings},
+    StructOpt,
+};
 
-mod days;
+use aoc24::{
+    answers::{check_report, AnswerFile},
+    bench::{bench_days, render_bench, Baseline},
+    days::{days_of, latest_year, registry, Registry, DAYS},
+    fetch::{fetch_missing, Fetched, Fetcher, DEFAULT_BASE_URL},
+    memory::CountingAllocator,
+    output::{describe_memory, render, render_days, Format, ProgressLine},
+    runner::{
+        configurable, configure, input_path, parse_seconds, run_day, run_days, select_example,
+        undefined_day, Answers, DaySelection, InputSource, Param, RunOptions,
+    },
+    scaffold::{module_name, scaffold},
+    watch::{check_examples, diff, Watcher},
+    Error, Part, Reporter,
+};
 
-use day::Day;
-use days::*;
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 #[derive(StructOpt)]
+#[structopt(setting = AppSettings::SubcommandsNegateReqs)]
 struct Opt {
-    #[structopt(name = "day")]
+    #[structopt(subcommand)]
+    command: Option<Command>,
+
+    /// Day to run: a number, a range such as `3..=9` or `3..10`, or `all`
+    #[structopt(name = "days")]
+    days: Option<DaySelection>,
+
+    /// Only solve the given part (1 or 2)
+    #[structopt(long)]
+    part: Option<Part>,
+
+    /// Compare the results against the recorded answers
+    #[structopt(long, conflicts_with = "record")]
+    check: bool,
+
+    /// Record the results as the known good answers
+    #[structopt(long)]
+    record: bool,
+
+    /// Read the input from this file instead, or from stdin when `-`
+    #[structopt(long, conflicts_with = "input-dir")]
+    input: Option<InputSource>,
+
+    /// Year to run, defaulting to the latest one with any solutions
+    #[structopt(long, global = true)]
+    year: Option<i32>,
+
+    /// Directory holding a folder per year with the `dayN.txt` inputs, `answers.toml` and the bench baseline
+    #[structopt(long, global = true, default_value = "input", parse(from_os_str))]
+    input_dir: PathBuf,
+
+    /// Root of the checkout holding `Cargo.toml` and `src/days`, used by `new` and `watch`
+    #[structopt(long, global = true, default_value = ".", parse(from_os_str))]
+    root: PathBuf,
+
+    /// Run the day's nth built-in example instead of the puzzle input, checking its answers
+    #[structopt(long, conflicts_with_all = &["input", "input-dir", "check", "record"])]
+    example: Option<usize>,
+
+    /// Override a puzzle parameter, e.g. `--param width=11 --param height=7`
+    #[structopt(long = "param", global = true, number_of_values = 1)]
+    params: Vec<Param>,
+
+    /// Number of days to run at once, defaulting to one per CPU
+    #[structopt(short, long)]
+    jobs: Option<usize>,
+
+    /// Don't show the progress of long running days
+    #[structopt(short, long)]
+    quiet: bool,
+
+    /// Report the peak heap usage of each day and part
+    #[structopt(long)]
+    memory: bool,
+
+    /// Give up on any part that takes longer than this many seconds
+    #[structopt(long, parse(try_from_str = parse_seconds))]
+    timeout: Option<Duration>,
+
+    /// Output format: text, json or csv
+    #[structopt(long, default_value = "text")]
+    format: Format,
+}
+
+#[derive(StructOpt)]
+enum Command {
+    /// Time the selected days on their puzzle inputs
+    Bench(BenchOpt),
+    /// Download the puzzle inputs that are missing from the input directory
+    Fetch(FetchOpt),
+    /// List the available days
+    List,
+    /// Generate and register the module for a new day
+    New(NewOpt),
+    /// Re-run a day and its examples whenever its input or source changes
+    Watch(WatchOpt),
+}
+
+#[derive(StructOpt)]
+struct BenchOpt {
+    /// Days to benchmark, selected as for a normal run
+    #[structopt(default_value = "all")]
+    days: DaySelection,
+
+    /// Number of timed runs per day
+    #[structopt(short = "n", long, default_value = "10")]
+    iterations: usize,
+
+    /// Save the mean times as the new baseline
+    #[structopt(long)]
+    save_baseline: bool,
+
+    /// Compare against the saved baseline, failing if any day regressed
+    #[structopt(long)]
+    compare: bool,
+
+    /// Slowdown, in percent, above which a day counts as a regression
+    #[structopt(long, default_value = "10")]
+    threshold: f64,
+}
+
+#[derive(StructOpt)]
+struct FetchOpt {
+    /// Days to download, selected as for a normal run
+    #[structopt(default_value = "all")]
+    days: DaySelection,
+
+    /// Session cookie of a logged in Advent of Code account
+    #[structopt(long, env = "SESSION", hide_env_values = true)]
+    session: String,
+
+    /// Server to download from, such as a local mock
+    #[structopt(long, env = "AOC_BASE_URL", default_value = DEFAULT_BASE_URL)]
+    base_url: String,
+
+    /// Minimum number of seconds between requests
+    #[structopt(long, default_value = "1", parse(try_from_str = parse_seconds))]
+    interval: Duration,
+}
+
+#[derive(StructOpt)]
+struct NewOpt {
+    /// Day to create
     day: i32,
+
+    /// Puzzle title
+    #[structopt(long, default_value = "")]
+    title: String,
 }
 
-fn default_error_handler<E: Debug, R>(error: E) -> R {
-    println!("{:#?}", error);
-    exit(1);
+#[derive(StructOpt)]
+struct WatchOpt {
+    /// Day to watch
+    day: i32,
+}
+
+const ANSWERS_FILE: &str = "answers.toml";
+const BASELINE_FILE: &str = "bench-baseline.csv";
+
+fn default_error_handler<R>(error: Error) -> R {
+    eprintln!("error: {}", error);
+    exit(error.exit_code());
+}
+
+/// The selected year, failing if it has no registered days.
+fn year(opt: &Opt) -> i32 {
+    let year = opt.year.or_else(latest_year).unwrap_or_default();
+    if days_of(year).next().is_none() {
+        default_error_handler::<()>(Error::invalid_config(format!(
+            "no days registered for {}",
+            year
+        )));
+    }
+    year
 }
 
 fn main() {
     let opt = Opt::from_args();
-    let mut programs: HashMap<i32, Box<dyn Day>> = HashMap::new();
-    programs.insert(1, Box::new(day1::Instance));
-    programs.insert(2, Box::new(day2::Instance));
-    programs.insert(3, Box::new(day3::Instance));
-    programs.insert(4, Box::new(day4::Instance));
-    programs.insert(5, Box::new(day5::Instance));
-    programs.insert(6, Box::new(day6::Instance));
-    programs.insert(7, Box::new(day7::Instance));
-    programs.insert(8, Box::new(day8::Instance));
-    programs.insert(9, Box::new(day9::Instance));
-    programs.insert(10, Box::new(day10::Instance));
-    programs.insert(11, Box::new(day11::Instance));
-    programs.insert(12, Box::new(day12::Instance));
-    programs.insert(13, Box::new(day13::Instance));
-    programs.insert(14, Box::new(day14::Instance::default()));
-    programs.insert(15, Box::new(day15::Instance));
-    programs.insert(16, Box::new(day16::Instance));
-    programs.insert(17, Box::new(day17::Instance));
-    programs.insert(18, Box::new(day18::Instance::default()));
-    programs.insert(19, Box::new(day19::Instance));
-    programs.insert(20, Box::new(day20::Instance::default()));
-    programs.insert(21, Box::new(day21::Instance));
-    programs.insert(22, Box::new(day22::Instance));
-    programs.insert(23, Box::new(day23::Instance));
-    programs.insert(24, Box::new(day24::Instance));
-    programs.insert(25, Box::new(day25::Instance));
-
-    let program = programs
-        .get(&opt.day)
-        .unwrap_or_else(|| default_error_handler(format!("Undefined day: {}", opt.day).as_str()));
-    let file_contents =
-        read_to_string(format!("input/day{}.txt", opt.day)).unwrap_or_else(default_error_handler);
-    let result = program
-        .run(file_contents)
+    let programs = registry();
+    match (&opt.command, &opt.days) {
+        (Some(Command::Bench(bench_opt)), _) => bench(&opt, bench_opt, programs),
+        (Some(Command::Fetch(fetch_opt)), _) => fetch(&opt, fetch_opt),
+        (Some(Command::List), _) => print!("{}", render_days(DAYS)),
+        (Some(Command::New(new_opt)), _) => new(&opt, new_opt),
+        (Some(Command::Watch(watch_opt)), _) => watch(&opt, watch_opt),
+        (None, Some(selection)) => run(&opt, selection, programs),
+        (None, None) => clap::Error::with_description(
+            "The following required arguments were not provided:\n    <days>",
+            clap::ErrorKind::MissingRequiredArgument,
+        )
+        .exit(),
+    }
+}
+
+fn run(opt: &Opt, selection: &DaySelection, mut programs: Registry) {
+    let year = year(opt);
+    if let Some(jobs) = opt.jobs {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build_global()
+            .map_err(|e| Error::invalid_config(format!("invalid --jobs: {}", e)))
+            .unwrap_or_else(default_error_handler);
+    }
+    let mut source = opt
+        .input
+        .clone()
+        .unwrap_or_else(|| InputSource::Dir(opt.input_dir.clone()));
+    if source.is_single() && !selection.is_single() {
+        default_error_handler::<()>(Error::invalid_config(
+            "--input can only be used when running a single day",
+        ));
+    }
+    let answers_path = opt.input_dir.join(year.to_string()).join(ANSWERS_FILE);
+
+    let days = selection.days(days_of(year));
+
+    let mut part = opt.part;
+    let mut expected = None;
+    if let Some(number) = opt.example {
+        if !selection.is_single() {
+            default_error_handler::<()>(Error::invalid_config(
+                "--example can only be used when running a single day",
+            ));
+        }
+        let day = days[0];
+        let Some(program) = programs.get_mut(&(year, day)) else {
+            default_error_handler(undefined_day(year, day))
+        };
+        let example =
+            select_example(configurable(program), number).unwrap_or_else(default_error_handler);
+        source = InputSource::Text(example.input.to_owned());
+        part = part.or(example.part());
+        let mut answer_file = AnswerFile::default();
+        answer_file.record(day, &Answers::from(&example));
+        expected = Some(answer_file);
+    }
+
+    configure(&mut programs, year, &days, &opt.params).unwrap_or_else(default_error_handler);
+    // Progress would only get in the way of structured output or a redirected stderr.
+    let progress = (opt.format == Format::Text && !opt.quiet && io::stderr().is_terminal())
+        .then(|| Arc::new(ProgressLine::default()));
+    let options = RunOptions {
+        part,
+        timeout: opt.timeout,
+        memory: opt.memory,
+        reporter: progress.clone().map(|p| p as Arc<dyn Reporter>),
+    };
+    let start = Instant::now();
+    let runs = run_days(&programs, year, &days, &source, &options);
+    let elapsed = start.elapsed();
+    if let Some(progress) = progress {
+        progress.clear();
+    }
+
+    if opt.format != Format::Text {
+        print!("{}", render(opt.format, &runs, elapsed));
+    } else if selection.is_single() {
+        let answers = runs[0].result.as_ref().unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            exit(e.exit_code());
+        });
+
+        if let Some(v) = &answers.part1 {
+            println!("Part 1: {}", v)
+        }
+        if let Some(v) = &answers.part2 {
+            println!("Part 2: {}", v)
+        }
+        if let Some(memory) = &runs[0].memory {
+            println!("Memory: {}", describe_memory(memory));
+        }
+    } else {
+        print!("{}", render(opt.format, &runs, elapsed));
+    }
+
+    if opt.record {
+        let mut answer_file = AnswerFile::load(&answers_path).unwrap_or_else(default_error_handler);
+        for run in &runs {
+            if let Ok(answers) = &run.result {
+                answer_file.record(run.day, answers);
+            }
+        }
+        answer_file
+            .save(&answers_path)
+            .unwrap_or_else(default_error_handler);
+    }
+
+    if opt.check {
+        expected = Some(AnswerFile::load(&answers_path).unwrap_or_else(default_error_handler));
+    }
+    let check_failed = expected.is_some_and(|answer_file| {
+        let (report, failed) = check_report(&answer_file, &runs);
+        // Keep stdout machine readable when a structured format was requested.
+        if opt.format == Format::Text {
+            print!("{}", report);
+        } else {
+            eprint!("{}", report);
+        }
+        failed
+    });
+
+    if let Some(Err(e)) = runs.iter().map(|run| &run.result).find(|r| r.is_err()) {
+        exit(e.exit_code());
+    }
+    if check_failed {
+        exit(1);
+    }
+}
+
+fn bench(opt: &Opt, bench_opt: &BenchOpt, mut programs: Registry) {
+    let year = year(opt);
+    let days = bench_opt.days.days(days_of(year));
+    configure(&mut programs, year, &days, &opt.params).unwrap_or_else(default_error_handler);
+    let source = InputSource::Dir(opt.input_dir.clone());
+    let baseline_path = opt.input_dir.join(year.to_string()).join(BASELINE_FILE);
+
+    let benches = bench_days(&programs, year, &days, &source, bench_opt.iterations);
+
+    let baseline = bench_opt
+        .compare
+        .then(|| Baseline::load(&baseline_path).unwrap_or_else(default_error_handler));
+    let (table, regressed) = render_bench(&benches, baseline.as_ref(), bench_opt.threshold / 100.0);
+    print!("{}", table);
+
+    if bench_opt.save_baseline {
+        let mut baseline = Baseline::load(&baseline_path).unwrap_or_else(default_error_handler);
+        baseline.record(&benches);
+        baseline
+            .save(&baseline_path)
+            .unwrap_or_else(default_error_handler);
+    }
+
+    if let Some(Err(e)) = benches.iter().map(|b| &b.result).find(|r| r.is_err()) {
+        exit(e.exit_code());
+    }
+    if regressed {
+        exit(1);
+    }
+}
+
+fn fetch(opt: &Opt, fetch_opt: &FetchOpt) {
+    let year = year(opt);
+    let days = fetch_opt.days.days(days_of(year));
+    let mut fetcher = Fetcher::new(&fetch_opt.base_url, &fetch_opt.session, fetch_opt.interval);
+
+    for (day, result) in fetch_missing(&mut fetcher, &opt.input_dir, year, &days) {
+        match result {
+            Ok(Fetched::Present) => println!("Day {}: already present", day),
+            Ok(Fetched::Downloaded) => println!("Day {}: downloaded", day),
+            Err(e) => {
+                println!("Day {}: {}", day, e);
+                exit(e.exit_code());
+            }
+        }
+    }
+}
+
+fn new(opt: &Opt, new_opt: &NewOpt) {
+    let year = opt.year.or_else(latest_year).unwrap_or_default();
+    let days_dir = opt.root.join("src/days");
+    let created = scaffold(&days_dir, &opt.input_dir, year, new_opt.day, &new_opt.title)
         .unwrap_or_else(default_error_handler);
+    for path in created {
+        println!("Created {}", path.display());
+    }
+}
+
+/// Carries the last answers over when watch mode restarts itself after a rebuild.
+const PREVIOUS_ANSWERS_VAR: &str = "AOC24_WATCH_PREVIOUS";
 
-    println!("Part 1: {}", result.part1);
-    if let Some(v) = result.part2 {
-        println!("Part 2: {}", v)
+fn watch(opt: &Opt, watch_opt: &WatchOpt) {
+    let year = year(opt);
+    let day = watch_opt.day;
+    let Some(registration) = DAYS.iter().find(|r| (r.year, r.day) == (year, day)) else {
+        default_error_handler(undefined_day(year, day))
+    };
+
+    let days_dir = opt.root.join("src/days");
+    let module = module_name(year, day);
+    let sources = [
+        days_dir.join(format!("{}.rs", module)),
+        days_dir.join("examples").join(format!("{}.txt", module)),
+    ];
+    let input = input_path(&opt.input_dir, year, day);
+    let mut watcher = Watcher::new(sources.iter().cloned().chain([input.clone()]));
+
+    let mut previous = env::var(PREVIOUS_ANSWERS_VAR)
+        .ok()
+        .and_then(|answers| answers.parse::<AnswerFile>().ok());
+    loop {
+        let mut programs = registry();
+        configure(&mut programs, year, &[day], &opt.params).unwrap_or_else(default_error_handler);
+        let options = RunOptions {
+            timeout: opt.timeout,
+            ..RunOptions::default()
+        };
+        let source = InputSource::Dir(opt.input_dir.clone());
+        let run = run_day(year, day, programs.get(&(year, day)), &source, &options);
+
+        println!(
+            "== Day {}: {} ({:.2?})",
+            day, registration.title, run.elapsed
+        );
+        print!(
+            "{}",
+            diff(previous.as_ref().and_then(|p| p.get(day)), &run.result)
+        );
+        print!("{}", check_examples(registration, opt.timeout));
+        if let Ok(answers) = &run.result {
+            let mut answer_file = AnswerFile::default();
+            answer_file.record(day, answers);
+            previous = Some(answer_file);
+        }
+
+        // Input changes only need a re-run, while source changes need a new binary.
+        while watcher
+            .wait(Duration::from_millis(200))
+            .iter()
+            .any(|path| sources.contains(path))
+        {
+            restart(&opt.root, previous.as_ref());
+        }
+    }
+}
+
+/// Rebuilds the binary and replaces this process with the new one, returning only
+/// if the build failed.
+fn restart(root: &Path, previous: Option<&AnswerFile>) {
+    println!("== Source changed, rebuilding");
+    // Resolved before building, since Linux reports a replaced binary as deleted.
+    let exe = env::current_exe().unwrap_or_else(|e| default_error_handler(Error::Io(e)));
+    let mut cargo = process::Command::new("cargo");
+    cargo.args(["build", "--quiet", "--manifest-path"]);
+    cargo.arg(root.join("Cargo.toml"));
+    if !cfg!(debug_assertions) {
+        cargo.arg("--release");
+    }
+    match cargo.status() {
+        Ok(status) if status.success() => {}
+        Ok(_) => return println!("== Build failed, waiting for changes"),
+        Err(e) => default_error_handler(Error::Io(e)),
+    }
+
+    let mut command = process::Command::new(exe);
+    command.args(env::args_os().skip(1));
+    if let Some(previous) = previous {
+        command.env(PREVIOUS_ANSWERS_VAR, previous.to_string());
+    }
+    // `exec` only returns when it failed to start the new binary.
+    #[cfg(unix)]
+    default_error_handler::<()>(Error::Io(std::os::unix::process::CommandExt::exec(
+        &mut command,
+    )));
+    #[cfg(not(unix))]
+    match command.status() {
+        Ok(status) => exit(status.code().unwrap_or(1)),
+        Err(e) => default_error_handler(Error::Io(e)),
     }
 }
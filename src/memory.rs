@@ -0,0 +1,131 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Global allocator that keeps count of the heap memory held by each thread, so
+/// that [`measure`] can report the peak usage of a piece of code. The binary
+/// installs it with `#[global_allocator]`.
+pub struct CountingAllocator;
+
+static COUNTING: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Bytes allocated minus bytes freed by this thread.
+    static CURRENT: Cell<isize> = const { Cell::new(0) };
+    /// Highest value of `CURRENT` since the innermost [`measure`] started.
+    static PEAK: Cell<isize> = const { Cell::new(0) };
+}
+
+fn record(delta: isize) {
+    if !COUNTING.load(Ordering::Relaxed) {
+        COUNTING.store(true, Ordering::Relaxed);
+    }
+    // The thread locals are gone while a thread shuts down, which is fine to miss.
+    let _ = CURRENT.try_with(|current| {
+        let now = current.get() + delta;
+        current.set(now);
+        let _ = PEAK.try_with(|peak| peak.set(peak.get().max(now)));
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(-(layout.size() as isize));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size as isize - layout.size() as isize);
+        }
+        new_ptr
+    }
+}
+
+/// Whether the [`CountingAllocator`] is installed, without which [`measure`]
+/// always reports zero.
+pub fn is_counting() -> bool {
+    COUNTING.load(Ordering::Relaxed)
+}
+
+/// Runs `f`, returning its result along with the most heap memory it held at
+/// once on the current thread, on top of what was held before.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    /// Folds the peak of a nested measurement back into the enclosing one, even
+    /// if `f` panics.
+    struct Restore(isize);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let _ = PEAK.try_with(|peak| peak.set(peak.get().max(self.0)));
+        }
+    }
+
+    let start = CURRENT.with(Cell::get);
+    let restore = Restore(PEAK.with(|peak| peak.replace(start)));
+    let result = f();
+    let peak = PEAK.with(Cell::get);
+    drop(restore);
+    (result, (peak - start).max(0) as usize)
+}
+
+/// Formats a number of bytes with binary units, e.g. `1.50 MiB`.
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_measure() {
+        let mib = 1 << 20;
+        let ((), outer) = measure(|| {
+            let ((), inner) = measure(|| drop(vec![0u8; mib]));
+            assert!((mib..mib + 1024).contains(&inner));
+            drop(vec![0u8; 1024]);
+        });
+        assert!((mib..mib + 1024).contains(&outer));
+        assert!(is_counting());
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.50 KiB");
+        assert_eq!(format_bytes(3 << 20), "3.00 MiB");
+    }
+}
@@ -0,0 +1,489 @@
+use std::{
+    collections::BTreeMap,
+    io::{stderr, Write},
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    days::{
+        day::{Progress, Registration, Reporter},
+        error::Error,
+    },
+    memory::format_bytes,
+    runner::{DayRun, Memory},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(Error::invalid_config(format!(
+                "invalid format '{}', expected text, json or csv",
+                s
+            ))),
+        }
+    }
+}
+
+/// `elapsed` is the wall-clock time of the whole run, which is less than the
+/// sum of the days when they ran in parallel.
+pub fn render(format: Format, runs: &[DayRun], elapsed: Duration) -> String {
+    match format {
+        Format::Text => render_table(runs, elapsed),
+        Format::Json => render_json(runs, elapsed),
+        Format::Csv => render_csv(runs),
+    }
+}
+
+/// Memory columns are only shown when it was measured.
+pub fn render_table(runs: &[DayRun], elapsed: Duration) -> String {
+    let with_memory = runs.iter().any(|run| run.memory.is_some());
+    let rows: Vec<Vec<String>> = runs
+        .iter()
+        .map(|run| {
+            let (part1, part2) = match &run.result {
+                Ok(answers) => (
+                    answers.part1.clone().unwrap_or_default(),
+                    answers.part2.clone().unwrap_or_default(),
+                ),
+                Err(Error::Timeout(_)) => ("TIMEOUT".to_owned(), String::new()),
+                Err(Error::Panic(_)) => ("PANIC".to_owned(), String::new()),
+                Err(_) => ("ERROR".to_owned(), String::new()),
+            };
+            let mut row = vec![
+                run.day.to_string(),
+                part1,
+                part2,
+                format!("{:.2?}", run.elapsed),
+            ];
+            if with_memory {
+                let bytes = |bytes: Option<usize>| bytes.map(format_bytes).unwrap_or_default();
+                row.extend([
+                    bytes(run.memory.map(|m| m.peak)),
+                    bytes(run.memory.and_then(|m| m.part1)),
+                    bytes(run.memory.and_then(|m| m.part2)),
+                ]);
+            }
+            row
+        })
+        .collect();
+    let mut footer = vec![
+        "Total".to_owned(),
+        String::new(),
+        String::new(),
+        format!("{:.2?}", elapsed),
+    ];
+
+    let mut header = vec!["Day", "Part 1", "Part 2", "Time"];
+    let mut right_aligned = vec![true, false, false, true];
+    if with_memory {
+        header.extend(["Memory", "Part 1 mem", "Part 2 mem"]);
+        right_aligned.extend([true; 3]);
+        footer.resize(header.len(), String::new());
+    }
+    let mut table = layout_table(&header, &rows, Some(&footer), &right_aligned);
+    for run in runs {
+        if let Err(e) = &run.result {
+            table.push_str(&format!("Day {}: {}\n", run.day, e));
+        }
+    }
+    table
+}
+
+/// Describes a day's peak memory along with that of each part, e.g.
+/// `1.50 MiB (part 1: 512 B, part 2: 1.00 MiB)`.
+pub fn describe_memory(memory: &Memory) -> String {
+    let parts: Vec<String> = [(1, memory.part1), (2, memory.part2)]
+        .into_iter()
+        .filter_map(|(part, bytes)| Some(format!("part {}: {}", part, format_bytes(bytes?))))
+        .collect();
+    format!("{} ({})", format_bytes(memory.peak), parts.join(", "))
+}
+
+pub fn render_days(days: &[Registration]) -> String {
+    let rows: Vec<Vec<String>> = days
+        .iter()
+        .map(|r| vec![r.year.to_string(), r.day.to_string(), r.title.to_owned()])
+        .collect();
+    layout_table(&["Year", "Day", "Title"], &rows, None, &[true, true, false])
+}
+
+/// Lays out `rows` in columns under `header`, with `footer` set apart below them.
+pub fn layout_table(
+    header: &[&str],
+    rows: &[Vec<String>],
+    footer: Option<&Vec<String>>,
+    right_aligned: &[bool],
+) -> String {
+    let header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+
+    let mut widths = vec![0; header.len()];
+    for row in rows.iter().chain([&header]).chain(footer) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |row: &Vec<String>| {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .zip(right_aligned)
+            .map(|((cell, width), right)| {
+                if *right {
+                    format!("{:>width$}", cell, width = width)
+                } else {
+                    format!("{:<width$}", cell, width = width)
+                }
+            })
+            .collect();
+        cells.join(" | ").trim_end().to_owned() + "\n"
+    };
+    let separator = widths
+        .iter()
+        .map(|w| "-".repeat(*w))
+        .collect::<Vec<_>>()
+        .join("-+-")
+        + "\n";
+
+    let mut table = format_row(&header);
+    table.push_str(&separator);
+    for row in rows {
+        table.push_str(&format_row(row));
+    }
+    if let Some(footer) = footer {
+        table.push_str(&separator);
+        table.push_str(&format_row(footer));
+    }
+    table
+}
+
+/// Renders the runs as a single JSON object:
+///
+/// ```json
+/// {
+///   "days": [
+///     {"year": 2024, "day": 1, "part1": "11", "part2": "31", "elapsed_ns": 21100, "error": null},
+///     {"year": 2024, "day": 2, "part1": null, "part2": null, "elapsed_ns": 0, "error": {"kind": "io", "message": "..."}}
+///   ],
+///   "total_elapsed_ns": 21100
+/// }
+/// ```
+///
+/// When memory was measured, each day also has e.g.
+/// `"memory": {"peak_bytes": 4096, "part1_bytes": 1024, "part2_bytes": null}`.
+/// `total_elapsed_ns` is the wall-clock time of the whole run.
+pub fn render_json(runs: &[DayRun], elapsed: Duration) -> String {
+    let days = runs
+        .iter()
+        .map(|run| {
+            let (part1, part2, error) = match &run.result {
+                Ok(answers) => (
+                    json_option(&answers.part1),
+                    json_option(&answers.part2),
+                    "null".to_owned(),
+                ),
+                Err(e) => (
+                    "null".to_owned(),
+                    "null".to_owned(),
+                    format!(
+                        "{{\"kind\": {}, \"message\": {}}}",
+                        json_string(e.kind()),
+                        json_string(&e.to_string())
+                    ),
+                ),
+            };
+            let memory = run.memory.map_or(String::new(), |m| {
+                let bytes = |b: Option<usize>| b.map_or("null".to_owned(), |b| b.to_string());
+                format!(
+                    ", \"memory\": {{\"peak_bytes\": {}, \"part1_bytes\": {}, \"part2_bytes\": {}}}",
+                    m.peak,
+                    bytes(m.part1),
+                    bytes(m.part2)
+                )
+            });
+            format!(
+                "    {{\"year\": {}, \"day\": {}, \"part1\": {}, \"part2\": {}, \"elapsed_ns\": {}{}, \"error\": {}}}",
+                run.year,
+                run.day,
+                part1,
+                part2,
+                run.elapsed.as_nanos(),
+                memory,
+                error
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        "{{\n  \"days\": [\n{}\n  ],\n  \"total_elapsed_ns\": {}\n}}\n",
+        days.join(",\n"),
+        elapsed.as_nanos()
+    )
+}
+
+/// Renders one row per run, under the header `year,day,part1,part2,elapsed_ns,error_kind,error`.
+pub fn render_csv(runs: &[DayRun]) -> String {
+    let mut csv = "year,day,part1,part2,elapsed_ns,error_kind,error\n".to_owned();
+    for run in runs {
+        let fields = match &run.result {
+            Ok(answers) => [
+                answers.part1.clone().unwrap_or_default(),
+                answers.part2.clone().unwrap_or_default(),
+                String::new(),
+                String::new(),
+            ],
+            Err(e) => [
+                String::new(),
+                String::new(),
+                e.kind().to_owned(),
+                e.to_string(),
+            ],
+        };
+        let [part1, part2, error_kind, error] = fields.map(|f| csv_field(&f));
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            run.year,
+            run.day,
+            part1,
+            part2,
+            run.elapsed.as_nanos(),
+            error_kind,
+            error
+        ));
+    }
+    csv
+}
+
+/// Draws the progress of the running days on one line of stderr, which is
+/// redrawn at most every [`ProgressLine::INTERVAL`].
+#[derive(Default)]
+pub struct ProgressLine {
+    state: Mutex<ProgressState>,
+}
+
+#[derive(Default)]
+struct ProgressState {
+    days: BTreeMap<(i32, i32), Progress>,
+    drawn: Option<Instant>,
+}
+
+impl ProgressLine {
+    pub const INTERVAL: Duration = Duration::from_millis(100);
+    /// Kept short enough not to wrap, as a wrapped line can't be redrawn.
+    const WIDTH: usize = 79;
+
+    /// Removes the line, ready for the results to be printed.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.days.clear();
+        state.draw();
+    }
+}
+
+impl ProgressState {
+    fn draw(&mut self) {
+        let line: String = progress_line(&self.days)
+            .chars()
+            .take(ProgressLine::WIDTH)
+            .collect();
+        // Progress is only a nicety, so failing to show it isn't worth reporting.
+        let _ = write!(stderr(), "\r\x1b[K{}", line);
+        self.drawn = Some(Instant::now());
+    }
+}
+
+impl Reporter for ProgressLine {
+    fn report(&self, year: i32, day: i32, progress: Progress) {
+        let mut state = self.state.lock().unwrap();
+        state.days.insert((year, day), progress);
+        if state
+            .drawn
+            .is_none_or(|drawn| drawn.elapsed() >= Self::INTERVAL)
+        {
+            state.draw();
+        }
+    }
+
+    fn finish(&self, year: i32, day: i32) {
+        let mut state = self.state.lock().unwrap();
+        if state.days.remove(&(year, day)).is_some() {
+            state.draw();
+        }
+    }
+}
+
+/// Describes the progress of each day, e.g. `Day 6: 1200 of 5000 (24%) | Day 17: searching`.
+pub fn progress_line(days: &BTreeMap<(i32, i32), Progress>) -> String {
+    days.iter()
+        .map(|((_, day), progress)| match progress {
+            Progress::Step { done, total } => format!(
+                "Day {}: {} of {} ({}%)",
+                day,
+                done,
+                total,
+                done * 100 / (*total).max(1)
+            ),
+            Progress::Status(status) => format!("Day {}: {}", day, status),
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn json_option(value: &Option<String>) -> String {
+    value.as_deref().map_or("null".to_owned(), json_string)
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::Answers;
+
+    fn runs() -> Vec<DayRun> {
+        vec![
+            DayRun {
+                year: 2024,
+                day: 17,
+                result: Ok(Answers {
+                    part1: Some("4,6,3".to_owned()),
+                    part2: None,
+                }),
+                elapsed: Duration::from_micros(15),
+                memory: None,
+            },
+            DayRun {
+                year: 2024,
+                day: 18,
+                result: Err(Error::unsolvable("no \"path\"")),
+                elapsed: Duration::from_nanos(5),
+                memory: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_table() {
+        let table = render_table(&runs(), Duration::from_micros(12));
+        assert_eq!(
+            table,
+            "  Day | Part 1 | Part 2 |    Time
+------+--------+--------+--------
+   17 | 4,6,3  |        | 15.00µs
+   18 | ERROR  |        |  5.00ns
+------+--------+--------+--------
+Total |        |        | 12.00µs
+Day 18: no solution: no \"path\"
+"
+        );
+    }
+
+    #[test]
+    fn test_memory() {
+        let mut runs = runs();
+        runs[0].memory = Some(Memory {
+            peak: 3 << 20,
+            part1: Some(512),
+            part2: None,
+        });
+        assert_eq!(
+            render_table(&runs, Duration::from_micros(12)),
+            "  Day | Part 1 | Part 2 |    Time |   Memory | Part 1 mem | Part 2 mem
+------+--------+--------+---------+----------+------------+-----------
+   17 | 4,6,3  |        | 15.00µs | 3.00 MiB |      512 B |
+   18 | ERROR  |        |  5.00ns |          |            |
+------+--------+--------+---------+----------+------------+-----------
+Total |        |        | 12.00µs |          |            |
+Day 18: no solution: no \"path\"
+"
+        );
+        assert!(render_json(&runs, Duration::from_micros(12)).contains(
+            r#""elapsed_ns": 15000, "memory": {"peak_bytes": 3145728, "part1_bytes": 512, "part2_bytes": null}, "error": null}"#
+        ));
+        assert_eq!(
+            describe_memory(runs[0].memory.as_ref().unwrap()),
+            "3.00 MiB (part 1: 512 B)"
+        );
+    }
+
+    #[test]
+    fn test_progress_line() {
+        let mut days = BTreeMap::new();
+        assert_eq!(progress_line(&days), "");
+        days.insert((2024, 17), Progress::Status("searching digit 3".to_owned()));
+        days.insert(
+            (2024, 6),
+            Progress::Step {
+                done: 1200,
+                total: 5000,
+            },
+        );
+        assert_eq!(
+            progress_line(&days),
+            "Day 6: 1200 of 5000 (24%) | Day 17: searching digit 3"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            render_json(&runs(), Duration::from_micros(12)),
+            r#"{
+  "days": [
+    {"year": 2024, "day": 17, "part1": "4,6,3", "part2": null, "elapsed_ns": 15000, "error": null},
+    {"year": 2024, "day": 18, "part1": null, "part2": null, "elapsed_ns": 5, "error": {"kind": "unsolvable", "message": "no solution: no \"path\""}}
+  ],
+  "total_elapsed_ns": 12000
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            render_csv(&runs()),
+            r#"year,day,part1,part2,elapsed_ns,error_kind,error
+2024,17,"4,6,3",,15000,,
+2024,18,,,5,unsolvable,"no solution: no ""path"""
+"#
+        );
+    }
+}
@@ -0,0 +1,827 @@
+use std::{
+    fs::read_to_string,
+    io::{self, Read},
+    ops::RangeInclusive,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+
+use crate::{
+    days::{
+        day::{Context, DayResult, Example, Part, Program, Reporter, Solver},
+        error::Error,
+        Registry,
+    },
+    memory::{self, measure},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DaySelection {
+    All,
+    Range(RangeInclusive<i32>),
+}
+
+impl FromStr for DaySelection {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_day = |d: &str| {
+            d.trim()
+                .parse::<i32>()
+                .map_err(|e| Error::invalid_config(format!("invalid day '{}': {}", d, e)))
+        };
+
+        if s == "all" {
+            Ok(DaySelection::All)
+        } else if let Some((start, end)) = s.split_once("..=") {
+            Ok(DaySelection::Range(parse_day(start)?..=parse_day(end)?))
+        } else if let Some((start, end)) = s.split_once("..") {
+            let end = parse_day(end)?
+                .checked_sub(1)
+                .ok_or_else(|| Error::invalid_config(format!("invalid day range '{}'", s)))?;
+            Ok(DaySelection::Range(parse_day(start)?..=end))
+        } else {
+            let day = parse_day(s)?;
+            Ok(DaySelection::Range(day..=day))
+        }
+    }
+}
+
+impl DaySelection {
+    pub fn is_single(&self) -> bool {
+        match self {
+            DaySelection::All => false,
+            DaySelection::Range(range) => range.start() == range.end(),
+        }
+    }
+
+    pub fn days(&self, available: impl Iterator<Item = i32>) -> Vec<i32> {
+        match self {
+            DaySelection::All => {
+                let mut days: Vec<_> = available.collect();
+                days.sort();
+                days
+            }
+            DaySelection::Range(range) => range.clone().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Answers {
+    fn set(&mut self, part: Part, answer: Option<String>) {
+        match part {
+            Part::One => self.part1 = answer,
+            Part::Two => self.part2 = answer,
+        }
+    }
+}
+
+impl From<DayResult> for Answers {
+    fn from(result: DayResult) -> Self {
+        Answers {
+            part1: Some(result.part1),
+            part2: result.part2,
+        }
+    }
+}
+
+/// Where the puzzle input for a day is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// `{dir}/{year}/day{N}.txt` for every day.
+    Dir(PathBuf),
+    /// A single file, whichever day is run.
+    File(PathBuf),
+    Stdin,
+    /// Input held in memory, such as a built-in example.
+    Text(String),
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::Dir(PathBuf::from("input"))
+    }
+}
+
+impl FromStr for InputSource {
+    type Err = Error;
+
+    /// Parses an explicit `--input` argument, where `-` stands for stdin.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Err(Error::invalid_config("empty input path")),
+            "-" => Ok(InputSource::Stdin),
+            path => Ok(InputSource::File(PathBuf::from(path))),
+        }
+    }
+}
+
+/// Where a day's input lives within an input directory.
+pub fn input_path(dir: &Path, year: i32, day: i32) -> PathBuf {
+    dir.join(year.to_string()).join(format!("day{}.txt", day))
+}
+
+impl InputSource {
+    /// Whether the source can only provide the input for a single day.
+    pub fn is_single(&self) -> bool {
+        !matches!(self, InputSource::Dir(_))
+    }
+
+    pub fn read(&self, year: i32, day: i32) -> Result<String, Error> {
+        let path = match self {
+            InputSource::Dir(dir) => input_path(dir, year, day),
+            InputSource::File(path) => path.clone(),
+            InputSource::Text(text) => return Ok(text.clone()),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|e| io::Error::new(e.kind(), format!("stdin: {}", e)))?;
+                return Ok(input);
+            }
+        };
+        read_to_string(&path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)).into())
+    }
+}
+
+/// A `name=value` puzzle parameter override.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: String,
+    pub value: String,
+}
+
+impl FromStr for Param {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s.split_once('=').ok_or_else(|| {
+            Error::invalid_config(format!("invalid parameter '{}', expected name=value", s))
+        })?;
+        Ok(Param {
+            name: name.trim().to_owned(),
+            value: value.trim().to_owned(),
+        })
+    }
+}
+
+/// Sets every parameter the program declares, returning whether each was used.
+pub fn apply_params(program: &mut dyn Program, params: &[Param]) -> Result<Vec<bool>, Error> {
+    params
+        .iter()
+        .map(|param| {
+            if program.param_names().contains(&param.name.as_str()) {
+                program.configure(&param.name, &param.value)?;
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        })
+        .collect()
+}
+
+impl From<&Example> for Answers {
+    fn from(example: &Example) -> Self {
+        Answers {
+            part1: example.part1.map(|a| a.to_owned()),
+            part2: example.part2.map(|a| a.to_owned()),
+        }
+    }
+}
+
+/// Applies the parameters to the selected days, failing on any that no day takes.
+pub fn configure(
+    programs: &mut Registry,
+    year: i32,
+    days: &[i32],
+    params: &[Param],
+) -> Result<(), Error> {
+    let mut used = vec![false; params.len()];
+    for day in days {
+        if let Some(program) = programs.get_mut(&(year, *day)) {
+            let applied = apply_params(configurable(program), params)?;
+            used.iter_mut().zip(applied).for_each(|(u, a)| *u |= a);
+        }
+    }
+    match params.iter().zip(&used).find(|(_, used)| !**used) {
+        Some((param, _)) => Err(Error::invalid_config(format!(
+            "no selected day takes the parameter '{}'",
+            param.name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Configures the program for its 1-based `number`th example, returning the example.
+pub fn select_example(program: &mut dyn Program, number: usize) -> Result<Example, Error> {
+    let examples = program.examples();
+    let example = number
+        .checked_sub(1)
+        .and_then(|i| examples.get(i))
+        .ok_or_else(|| {
+            Error::invalid_config(format!(
+                "no example {}, this day has {}",
+                number,
+                examples.len()
+            ))
+        })?;
+    for (name, value) in example.params {
+        program.configure(name, value)?;
+    }
+    Ok(*example)
+}
+
+pub struct DayRun {
+    pub year: i32,
+    pub day: i32,
+    pub result: Result<Answers, Error>,
+    pub elapsed: Duration,
+    /// Only measured when asked for and the counting allocator is installed.
+    pub memory: Option<Memory>,
+}
+
+/// Peak heap usage in bytes of a whole day, and of each part it solved on top of
+/// the parsed input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Memory {
+    pub peak: usize,
+    pub part1: Option<usize>,
+    pub part2: Option<usize>,
+}
+
+impl Memory {
+    fn set(&mut self, part: Part, peak: usize) {
+        match part {
+            Part::One => self.part1 = Some(peak),
+            Part::Two => self.part2 = Some(peak),
+        }
+    }
+}
+
+/// How each day is run.
+#[derive(Clone, Default)]
+pub struct RunOptions {
+    /// Only solve this part.
+    pub part: Option<Part>,
+    /// Give up on a part that takes longer than this.
+    pub timeout: Option<Duration>,
+    /// Report the peak heap usage.
+    pub memory: bool,
+    /// Receives the progress of the solvers.
+    pub reporter: Option<Arc<dyn Reporter>>,
+}
+
+impl RunOptions {
+    /// A fresh context for solving one part of a day, with its own deadline.
+    fn context(&self, year: i32, day: i32) -> Context {
+        let context = self
+            .timeout
+            .map_or_else(Context::default, Context::with_timeout);
+        match &self.reporter {
+            Some(reporter) => context.reporting_to(Arc::clone(reporter), year, day),
+            None => context,
+        }
+    }
+}
+
+pub fn run_day(
+    year: i32,
+    day: i32,
+    program: Option<&Arc<dyn Program>>,
+    source: &InputSource,
+    options: &RunOptions,
+) -> DayRun {
+    let failed = |e: Error| DayRun {
+        year,
+        day,
+        result: Err(e),
+        elapsed: Duration::ZERO,
+        memory: None,
+    };
+
+    let Some(program) = program else {
+        return failed(undefined_day(year, day));
+    };
+    let input = match source.read(year, day) {
+        Ok(input) => input,
+        Err(e) => return failed(e),
+    };
+
+    let start = Instant::now();
+    let (result, memory) = match catch_panic(|| solve(program, year, day, input, options)) {
+        Ok((answers, memory)) => (Ok(answers), Some(memory)),
+        Err(e) => (Err(e), None),
+    };
+    if let Some(reporter) = &options.reporter {
+        reporter.finish(year, day);
+    }
+    DayRun {
+        year,
+        day,
+        result,
+        elapsed: start.elapsed(),
+        memory: memory.filter(|_| options.memory && memory::is_counting()),
+    }
+}
+
+/// Runs the days concurrently on the rayon thread pool, returning the runs in the order given.
+pub fn run_days(
+    programs: &Registry,
+    year: i32,
+    days: &[i32],
+    source: &InputSource,
+    options: &RunOptions,
+) -> Vec<DayRun> {
+    days.par_iter()
+        .map(|day| run_day(year, *day, programs.get(&(year, *day)), source, options))
+        .collect()
+}
+
+/// Runs `f`, turning a panic into an error so that one broken day doesn't take
+/// down the rest of the run.
+pub fn catch_panic<T>(f: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| Err(Error::panic(payload)))
+}
+
+/// Parses a duration given in (possibly fractional) seconds.
+pub fn parse_seconds(s: &str) -> Result<Duration, Error> {
+    s.parse()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| Error::invalid_config(format!("invalid duration '{}', expected seconds", s)))
+}
+
+/// Gives mutable access to a day, which is only possible before it starts running.
+pub fn configurable(program: &mut Arc<dyn Program>) -> &mut dyn Program {
+    Arc::get_mut(program).expect("days are configured before they are run")
+}
+
+pub fn undefined_day(year: i32, day: i32) -> Error {
+    Error::invalid_config(format!("Undefined day: {} day {}", year, day))
+}
+
+fn solve(
+    program: &Arc<dyn Program>,
+    year: i32,
+    day: i32,
+    input: String,
+    options: &RunOptions,
+) -> Result<(Answers, Memory), Error> {
+    let parts = match options.part {
+        None => vec![Part::One, Part::Two],
+        Some(part) => vec![part],
+    };
+    let mut answers = Answers::default();
+    let mut memory = Memory::default();
+
+    let Some(limit) = options.timeout else {
+        let (solved, peak) = measure(|| -> Result<(), Error> {
+            let solver = program.prepare(&input)?;
+            for part in parts {
+                let (answer, peak) =
+                    measure(|| solve_part(solver.as_ref(), part, &options.context(year, day)));
+                answers.set(part, answer?);
+                memory.set(part, peak);
+            }
+            Ok(())
+        });
+        solved?;
+        memory.peak = peak;
+        return Ok((answers, memory));
+    };
+
+    // Solve on a separate thread, so that a solver which never checks its context
+    // is left behind instead of holding up the rest of the run.
+    let (sender, receiver) = mpsc::channel();
+    let worker = {
+        let program = Arc::clone(program);
+        let parts = parts.clone();
+        let options = options.clone();
+        // The thread's peak over the whole day is its result.
+        thread::spawn(move || {
+            let ((), peak) = measure(|| {
+                let solver = match program.prepare(&input) {
+                    Ok(solver) => solver,
+                    Err(e) => return drop(sender.send(Message::Prepared(Err(e)))),
+                };
+                if sender.send(Message::Prepared(Ok(()))).is_err() {
+                    return;
+                }
+                for part in parts {
+                    let context = options.context(year, day);
+                    let (answer, peak) = measure(|| solve_part(solver.as_ref(), part, &context));
+                    if sender.send(Message::Solved(part, answer, peak)).is_err() {
+                        return;
+                    }
+                }
+            });
+            peak
+        })
+    };
+
+    // Parsing and each part get the whole limit to themselves, as the clock
+    // restarts with every message.
+    loop {
+        match receiver.recv_timeout(limit) {
+            Ok(Message::Prepared(prepared)) => prepared?,
+            Ok(Message::Solved(part, answer, peak)) => {
+                answers.set(part, answer?);
+                memory.set(part, peak);
+            }
+            Err(RecvTimeoutError::Timeout) => return Err(Error::Timeout(limit)),
+            // The thread has finished, either with every part sent or by panicking.
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    match worker.join() {
+        Ok(peak) => memory.peak = peak,
+        Err(panic) => panic::resume_unwind(panic),
+    }
+    Ok((answers, memory))
+}
+
+/// What the solving thread sends back, in order.
+enum Message {
+    Prepared(Result<(), Error>),
+    /// A part's answer, with the thread's peak memory while solving it.
+    Solved(Part, Result<Option<String>, Error>, usize),
+}
+
+fn solve_part(solver: &dyn Solver, part: Part, ctx: &Context) -> Result<Option<String>, Error> {
+    match part {
+        Part::One => solver.part1(ctx).map(Some),
+        Part::Two => solver.part2(ctx),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::day::{Day, Progress};
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!("all".parse(), Ok(DaySelection::All));
+        assert_eq!("7".parse(), Ok(DaySelection::Range(7..=7)));
+        assert_eq!("3..=9".parse(), Ok(DaySelection::Range(3..=9)));
+        assert_eq!("3..9".parse(), Ok(DaySelection::Range(3..=8)));
+        assert!("3..x".parse::<DaySelection>().is_err());
+        assert_eq!(
+            "1..-2147483648".parse::<DaySelection>(),
+            Err(Error::invalid_config("invalid day range '1..-2147483648'"))
+        );
+    }
+
+    #[test]
+    fn test_selected_days() {
+        assert!(DaySelection::Range(4..=4).is_single());
+        assert!(!DaySelection::All.is_single());
+        assert_eq!(DaySelection::All.days([3, 1, 2].into_iter()), vec![1, 2, 3]);
+        assert_eq!(
+            DaySelection::Range(2..=4).days([1].into_iter()),
+            vec![2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_params() {
+        assert_eq!(
+            "width = 11".parse(),
+            Ok(Param {
+                name: "width".to_owned(),
+                value: "11".to_owned()
+            })
+        );
+        assert!("width".parse::<Param>().is_err());
+
+        let mut day = crate::days::day20::Instance::default();
+        let params = ["threshold=50".parse().unwrap(), "width=3".parse().unwrap()];
+        assert_eq!(apply_params(&mut day, &params), Ok(vec![true, false]));
+        assert!(apply_params(&mut day, &["threshold=x".parse().unwrap()]).is_err());
+    }
+
+    #[test]
+    fn test_select_example() {
+        let mut day: Arc<dyn Program> = Arc::new(crate::days::day20::Instance::default());
+        let example = select_example(configurable(&mut day), 1).unwrap();
+        let run = run_day(
+            2024,
+            20,
+            Some(&day),
+            &InputSource::Text(example.input.to_owned()),
+            &RunOptions::default(),
+        );
+        assert_eq!(run.result, Ok(Answers::from(&example)));
+
+        assert!(select_example(configurable(&mut day), 0).is_err());
+        assert!(select_example(configurable(&mut day), 2).is_err());
+    }
+
+    #[test]
+    fn test_run_days_in_order() {
+        let days = [3, 1, 2, 26];
+        let runs = run_days(
+            &crate::days::registry(),
+            2024,
+            &days,
+            &InputSource::Text("1 2\n".to_owned()),
+            &RunOptions {
+                part: Some(Part::One),
+                ..RunOptions::default()
+            },
+        );
+        assert_eq!(runs.iter().map(|r| r.day).collect::<Vec<_>>(), days);
+        assert_eq!(runs[3].result, Err(undefined_day(2024, 26)));
+    }
+
+    #[test]
+    fn test_timeout() {
+        assert_eq!(parse_seconds("1.5"), Ok(Duration::from_millis(1500)));
+        assert!(parse_seconds("-1").is_err());
+        assert!(parse_seconds("soon").is_err());
+
+        assert_eq!(Context::default().check(), Ok(()));
+        assert_eq!(
+            Context::with_timeout(Duration::ZERO).check(),
+            Err(Error::Timeout(Duration::ZERO))
+        );
+
+        let programs = crate::days::registry();
+        let source = InputSource::Text("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n".to_owned());
+        let run = |timeout| {
+            let options = RunOptions {
+                timeout,
+                ..RunOptions::default()
+            };
+            run_day(2024, 1, programs.get(&(2024, 1)), &source, &options).result
+        };
+        assert_eq!(run(Some(Duration::from_secs(60))), run(None));
+        assert_eq!(
+            run(Some(Duration::from_secs(60))).unwrap().part2.as_deref(),
+            Some("31")
+        );
+    }
+
+    #[test]
+    fn test_memory() {
+        let programs = crate::days::registry();
+        let source = InputSource::Text("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n".to_owned());
+        for (timeout, memory) in [
+            (None, true),
+            (Some(Duration::from_secs(60)), true),
+            (None, false),
+        ] {
+            let options = RunOptions {
+                part: Some(Part::Two),
+                timeout,
+                memory,
+                ..RunOptions::default()
+            };
+            let run = run_day(2024, 1, programs.get(&(2024, 1)), &source, &options);
+            assert_eq!(run.memory.is_some(), memory);
+            if let Some(memory) = run.memory {
+                assert_eq!(memory.part1, None);
+                assert!(memory
+                    .part2
+                    .is_some_and(|part2| part2 > 0 && part2 <= memory.peak));
+            }
+        }
+    }
+
+    #[test]
+    fn test_progress() {
+        #[derive(Default)]
+        struct Recorder(std::sync::Mutex<Vec<(i32, Option<Progress>)>>);
+
+        impl Reporter for Recorder {
+            fn report(&self, _year: i32, day: i32, progress: Progress) {
+                self.0.lock().unwrap().push((day, Some(progress)));
+            }
+
+            fn finish(&self, _year: i32, day: i32) {
+                self.0.lock().unwrap().push((day, None));
+            }
+        }
+
+        let mut day: Arc<dyn Program> = Arc::new(crate::days::day18::Instance::default());
+        let example = select_example(configurable(&mut day), 1).unwrap();
+        let recorder = Arc::new(Recorder::default());
+        let options = RunOptions {
+            part: Some(Part::Two),
+            reporter: Some(recorder.clone()),
+            ..RunOptions::default()
+        };
+        let source = InputSource::Text(example.input.to_owned());
+        run_day(2024, 18, Some(&day), &source, &options);
+
+        let reported = recorder.0.lock().unwrap();
+        assert_eq!(
+            reported[..2],
+            [
+                (18, Some(Progress::Step { done: 0, total: 13 })),
+                (18, Some(Progress::Step { done: 1, total: 13 }))
+            ]
+        );
+        assert_eq!(reported.last(), Some(&(18, None)));
+    }
+
+    /// A day whose parser unwraps the first line as a pair of numbers.
+    struct Unwraps;
+
+    impl Day for Unwraps {
+        type Parsed = usize;
+
+        fn parse(&self, input: &str) -> Result<usize, Error> {
+            let (a, b) = input.trim().split_once(' ').unwrap();
+            Ok(a.len() + b.len())
+        }
+
+        fn part1(&self, parsed: &usize, _ctx: &Context) -> Result<String, Error> {
+            Ok(parsed.to_string())
+        }
+
+        fn part2(&self, _: &usize, _ctx: &Context) -> Result<Option<String>, Error> {
+            Ok(None)
+        }
+    }
+
+    /// A day that fails in every part except the one it's told to solve.
+    struct Solves(Part);
+
+    impl Day for Solves {
+        type Parsed = ();
+
+        fn parse(&self, _input: &str) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn part1(&self, _: &(), _ctx: &Context) -> Result<String, Error> {
+            match self.0 {
+                Part::One => Ok("1".to_owned()),
+                Part::Two => Err(Error::unsolvable("part 1 was solved")),
+            }
+        }
+
+        fn part2(&self, _: &(), _ctx: &Context) -> Result<Option<String>, Error> {
+            match self.0 {
+                Part::One => Err(Error::unsolvable("part 2 was solved")),
+                Part::Two => Ok(Some("2".to_owned())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_only_the_selected_part_is_solved() {
+        for timeout in [None, Some(Duration::from_secs(60))] {
+            for part in [Part::One, Part::Two] {
+                let mut programs = Registry::new();
+                programs.insert((2024, 1), Arc::new(Solves(part)) as Arc<dyn Program>);
+                let runs = run_days(
+                    &programs,
+                    2024,
+                    &[1],
+                    &InputSource::Text(String::new()),
+                    &RunOptions {
+                        part: Some(part),
+                        timeout,
+                        ..RunOptions::default()
+                    },
+                );
+                let mut expected = Answers::default();
+                match part {
+                    Part::One => expected.part1 = Some("1".to_owned()),
+                    Part::Two => expected.part2 = Some("2".to_owned()),
+                }
+                assert_eq!(runs[0].result, Ok(expected));
+            }
+        }
+    }
+
+    /// A day that takes `step` both to parse and to solve part 1, then stalls
+    /// in part 2 until it's cancelled, or forever if it never checks its context.
+    struct Stalls {
+        step: Duration,
+        checks: bool,
+    }
+
+    impl Day for Stalls {
+        type Parsed = ();
+
+        fn parse(&self, _input: &str) -> Result<(), Error> {
+            thread::sleep(self.step);
+            Ok(())
+        }
+
+        fn part1(&self, _: &(), _ctx: &Context) -> Result<String, Error> {
+            thread::sleep(self.step);
+            Ok("1".to_owned())
+        }
+
+        fn part2(&self, _: &(), ctx: &Context) -> Result<Option<String>, Error> {
+            loop {
+                if self.checks {
+                    ctx.check()?;
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_stalled_parts_time_out() {
+        let limit = Duration::from_secs(1);
+        let run = |day: Stalls, part| {
+            let day: Arc<dyn Program> = Arc::new(day);
+            let options = RunOptions {
+                part,
+                timeout: Some(limit),
+                ..RunOptions::default()
+            };
+            run_day(
+                2024,
+                1,
+                Some(&day),
+                &InputSource::Text(String::new()),
+                &options,
+            )
+            .result
+        };
+        // Parsing and part 1 each get the whole limit, so together they may
+        // take longer. Each leaves plenty of slack for a busy machine.
+        let slow = Stalls {
+            step: limit * 11 / 20,
+            checks: true,
+        };
+        assert_eq!(
+            run(slow, Some(Part::One)),
+            Ok(Answers {
+                part1: Some("1".to_owned()),
+                part2: None
+            })
+        );
+        for checks in [true, false] {
+            let stalls = Stalls {
+                step: Duration::ZERO,
+                checks,
+            };
+            assert_eq!(run(stalls, None), Err(Error::Timeout(limit)));
+        }
+    }
+
+    #[test]
+    fn test_panics_are_reported() {
+        let panicked = Err(Error::Panic(
+            "called `Option::unwrap()` on a `None` value".to_owned(),
+        ));
+        let mut programs = crate::days::registry();
+        programs.insert((2024, 1), Arc::new(Unwraps));
+        for timeout in [None, Some(Duration::from_secs(60))] {
+            let runs = run_days(
+                &programs,
+                2024,
+                &[1, 2],
+                &InputSource::Text("3\n".to_owned()),
+                &RunOptions {
+                    timeout,
+                    ..RunOptions::default()
+                },
+            );
+            assert_eq!(runs[0].result, panicked);
+            assert!(runs[1].result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_input_source() {
+        assert_eq!("-".parse(), Ok(InputSource::Stdin));
+        assert_eq!(
+            "alice/day3.txt".parse(),
+            Ok(InputSource::File(PathBuf::from("alice/day3.txt")))
+        );
+        assert!("".parse::<InputSource>().is_err());
+
+        let missing = InputSource::Dir(PathBuf::from("no/such/dir")).read(2024, 3);
+        match missing {
+            Err(Error::Io(e)) => {
+                assert!(e.to_string().starts_with("no/such/dir/2024/day3.txt: "))
+            }
+            _ => panic!("expected an i/o error"),
+        }
+    }
+}
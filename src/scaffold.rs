@@ -0,0 +1,291 @@
+use std::{
+    fs::{create_dir_all, read_to_string, write, OpenOptions},
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{days::error::Error, runner::input_path};
+
+/// The year whose days are named plainly `dayN`. Days of other years are
+/// prefixed with their year, e.g. `y2025_day1`.
+const DEFAULT_YEAR: i32 = 2024;
+
+const TEMPLATE: &str = r#"use super::{day::*, error::Error};
+
+pub const REGISTRATION: Registration = Registration {
+    year: $year,
+    day: $day,
+    title: $title,
+    new: || Box::new(Instance),
+};
+
+pub struct Instance;
+
+impl Day for Instance {
+    type Parsed = Vec<String>;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        params: &[],
+        part1: None,
+        part2: None,
+    }];
+
+    fn parse(&self, input: &str) -> Result<Vec<String>, Error> {
+        Ok(input.lines().map(|line| line.to_owned()).collect())
+    }
+
+    fn part1(&self, lines: &Vec<String>, _ctx: &Context) -> Result<String, Error> {
+        Ok(lines.len().to_string())
+    }
+
+    fn part2(&self, _lines: &Vec<String>, _ctx: &Context) -> Result<Option<String>, Error> {
+        Ok(None)
+    }
+}
+
+const EXAMPLE: &str = include_str!("examples/$module.txt");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        for example in Instance::EXAMPLES {
+            // TODO: replace with the example's answers, as in EXAMPLES.
+            assert_eq!(
+                Instance.run(example.input.to_owned()),
+                Ok(DayResult {
+                    part1: "TODO".to_owned(),
+                    part2: None,
+                })
+            );
+        }
+    }
+}
+"#;
+
+pub fn module_name(year: i32, day: i32) -> String {
+    if year == DEFAULT_YEAR {
+        format!("day{}", day)
+    } else {
+        format!("y{}_day{}", year, day)
+    }
+}
+
+/// The year and day of a module named by [`module_name`].
+fn module_day(module: &str) -> Option<(i32, i32)> {
+    match module.split_once("_day") {
+        Some((year, day)) => Some((year.strip_prefix('y')?.parse().ok()?, day.parse().ok()?)),
+        None => Some((DEFAULT_YEAR, module.strip_prefix("day")?.parse().ok()?)),
+    }
+}
+
+pub fn render_module(year: i32, day: i32, title: &str) -> String {
+    TEMPLATE
+        .replace("$year", &year.to_string())
+        .replace("$day", &day.to_string())
+        .replace("$title", &format!("{:?}", title))
+        .replace("$module", &module_name(year, day))
+}
+
+/// Declares `module` and adds it to the `days!` list in the source of
+/// `days/mod.rs`, keeping the declarations sorted the way rustfmt sorts them
+/// and the list in year and day order.
+pub fn register(days_mod: &str, module: &str) -> Result<String, Error> {
+    let invalid = |message: &str| Error::invalid_config(format!("days/mod.rs: {}", message));
+    let start = days_mod
+        .find("\ndays!(")
+        .ok_or_else(|| invalid("no days! invocation"))?
+        + "\ndays!(".len();
+    let end = start
+        + days_mod[start..]
+            .find(");")
+            .ok_or_else(|| invalid("unterminated days! invocation"))?;
+
+    let mut modules: Vec<&str> = days_mod[start..end]
+        .split(',')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .collect();
+    if modules.contains(&module) {
+        return Err(Error::invalid_config(format!(
+            "{} is already registered",
+            module
+        )));
+    }
+    modules.push(module);
+    modules.sort_by_key(|m| module_day(m));
+
+    let mut list = String::from("\n");
+    let mut line = String::new();
+    for module in modules {
+        if !line.is_empty() && 4 + line.len() + 1 + module.len() + 1 > 100 {
+            list.push_str(&format!("    {}\n", line.trim_end()));
+            line.clear();
+        }
+        line.push_str(&format!("{}, ", module));
+    }
+    list.push_str(&format!("    {}\n", line.trim_end()));
+
+    let days_mod = format!("{}{}{}", &days_mod[..start], list, &days_mod[end..]);
+    let declaration = format!("pub mod {};\n", module);
+    let mut lines: Vec<&str> = days_mod.split_inclusive('\n').collect();
+    let declared = |line: &str| {
+        line.strip_prefix("pub mod ")
+            .and_then(|rest| rest.trim_end().strip_suffix(';'))
+            .map(str::to_owned)
+    };
+    let last = lines
+        .iter()
+        .rposition(|line| declared(line).is_some())
+        .ok_or_else(|| invalid("no module declarations"))?;
+    let at = lines
+        .iter()
+        .position(|line| declared(line).is_some_and(|m| m.as_str() > module))
+        .unwrap_or(last + 1);
+    lines.insert(at, &declaration);
+    Ok(lines.concat())
+}
+
+/// Generates the module for a new day in `days_dir`, registers it and creates
+/// an empty example and puzzle input, returning the files it created. Existing
+/// examples and inputs are left alone; an existing module is an error.
+pub fn scaffold(
+    days_dir: &Path,
+    input_dir: &Path,
+    year: i32,
+    day: i32,
+    title: &str,
+) -> Result<Vec<PathBuf>, Error> {
+    if !(1..=25).contains(&day) {
+        return Err(Error::invalid_config(format!(
+            "day {} is not between 1 and 25",
+            day
+        )));
+    }
+    let module = module_name(year, day);
+    let module_path = days_dir.join(format!("{}.rs", module));
+    let mod_path = days_dir.join("mod.rs");
+    let registered = register(&read_to_string(&mod_path)?, &module)?;
+
+    if !create_new(&module_path, &render_module(year, day, title))? {
+        return Err(Error::invalid_config(format!(
+            "{} already exists",
+            module_path.display()
+        )));
+    }
+    write(&mod_path, registered)?;
+
+    let mut created = vec![module_path];
+
+    let example_path = days_dir.join("examples").join(format!("{}.txt", module));
+    for path in [example_path, input_path(input_dir, year, day)] {
+        if create_new(&path, "")? {
+            created.push(path);
+        }
+    }
+    Ok(created)
+}
+
+/// Writes a file that doesn't exist yet, returning false if it does.
+fn create_new(path: &Path, contents: &str) -> Result<bool, Error> {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(mut file) => {
+            file.write_all(contents.as_bytes())?;
+            Ok(true)
+        }
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(io::Error::new(e.kind(), format!("{}: {}", path.display(), e)).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs::remove_dir_all};
+
+    use super::*;
+
+    const DAYS_MOD: &str = "pub mod day;
+pub mod day1;
+pub mod day25;
+pub mod util;
+
+days!(
+    day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13, day14, day15,
+    day16, day17, day18, day19, day20, day21, day22, day23, day24, day25,
+);
+
+pub fn registry() {}
+";
+
+    #[test]
+    fn test_register() {
+        assert_eq!(module_day("day7"), Some((2024, 7)));
+        assert_eq!(module_day("y2025_day12"), Some((2025, 12)));
+
+        let registered = register(DAYS_MOD, "y2025_day1").unwrap();
+        assert_eq!(
+            registered,
+            "pub mod day;
+pub mod day1;
+pub mod day25;
+pub mod util;
+pub mod y2025_day1;
+
+days!(
+    day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13, day14, day15,
+    day16, day17, day18, day19, day20, day21, day22, day23, day24, day25, y2025_day1,
+);
+
+pub fn registry() {}
+"
+        );
+        assert!(register(&registered, "y2025_day1").is_err());
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let dir = env::temp_dir().join(format!("aoc24-scaffold-{}", std::process::id()));
+        let _ = remove_dir_all(&dir);
+        let days_dir = dir.join("days");
+        let input_dir = dir.join("input");
+        create_dir_all(&days_dir).unwrap();
+        write(days_dir.join("mod.rs"), DAYS_MOD).unwrap();
+        create_dir_all(input_dir.join("2025")).unwrap();
+        write(input_path(&input_dir, 2025, 3), "fetched\n").unwrap();
+
+        let created = scaffold(&days_dir, &input_dir, 2025, 3, "Mull \"It\" Over").unwrap();
+        assert_eq!(
+            created,
+            [
+                days_dir.join("y2025_day3.rs"),
+                days_dir.join("examples/y2025_day3.txt")
+            ]
+        );
+        let module = read_to_string(days_dir.join("y2025_day3.rs")).unwrap();
+        assert!(
+            module.contains("    year: 2025,\n    day: 3,\n    title: \"Mull \\\"It\\\" Over\",\n")
+        );
+        assert!(module.contains("include_str!(\"examples/y2025_day3.txt\")"));
+        assert!(read_to_string(days_dir.join("mod.rs"))
+            .unwrap()
+            .contains("day25, y2025_day3,\n"));
+        assert!(read_to_string(days_dir.join("mod.rs"))
+            .unwrap()
+            .contains("pub mod util;\npub mod y2025_day3;\n"));
+        assert_eq!(
+            read_to_string(input_path(&input_dir, 2025, 3)).unwrap(),
+            "fetched\n"
+        );
+
+        assert!(scaffold(&days_dir, &input_dir, 2025, 3, "").is_err());
+        assert!(scaffold(&days_dir, &input_dir, 2025, 26, "").is_err());
+
+        remove_dir_all(&dir).unwrap();
+    }
+}
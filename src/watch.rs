@@ -0,0 +1,193 @@
+use std::{
+    fs::metadata,
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    answers::AnswerFile,
+    days::{
+        day::{Part, Program, Registration},
+        error::Error,
+    },
+    runner::{configurable, run_day, select_example, Answers, InputSource, RunOptions},
+};
+
+/// Polls a set of files for changes, since there's no portable way to be notified.
+pub struct Watcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl Watcher {
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        Watcher {
+            files: paths
+                .into_iter()
+                .map(|path| {
+                    let modified = modified(&path);
+                    (path, modified)
+                })
+                .collect(),
+        }
+    }
+
+    /// The files that were modified, created or removed since the last call.
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for (path, last) in &mut self.files {
+            let modified = modified(path);
+            if modified != *last {
+                *last = modified;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+
+    /// Blocks until any of the files change.
+    pub fn wait(&mut self, poll: Duration) -> Vec<PathBuf> {
+        loop {
+            let changed = self.changed();
+            if !changed.is_empty() {
+                return changed;
+            }
+            sleep(poll);
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Describes the result of a run, noting the answers that differ from `previous`.
+pub fn diff(previous: Option<&Answers>, current: &Result<Answers, Error>) -> String {
+    let answers = match current {
+        Ok(answers) => answers,
+        Err(e) => return format!("error: {}\n", e),
+    };
+
+    let mut diff = String::new();
+    for (part, answer) in [(Part::One, &answers.part1), (Part::Two, &answers.part2)] {
+        let before = previous.and_then(|p| match part {
+            Part::One => p.part1.as_ref(),
+            Part::Two => p.part2.as_ref(),
+        });
+        let Some(answer) = answer else {
+            continue;
+        };
+        diff.push_str(&format!("Part {}: {}", part, answer));
+        match before {
+            Some(before) if before != answer => diff.push_str(&format!(" (was {})", before)),
+            None if previous.is_some() => diff.push_str(" (new)"),
+            _ => {}
+        }
+        diff.push('\n');
+    }
+    diff
+}
+
+/// Runs each of the day's examples on a fresh instance, so that their parameters
+/// don't leak into the run on the real input, and reports the verdict per part.
+pub fn check_examples(registration: &Registration, timeout: Option<Duration>) -> String {
+    let examples = (registration.new)().examples().len();
+    let mut report = String::new();
+    for number in 1..=examples {
+        let mut program: Arc<dyn Program> = Arc::from((registration.new)());
+        let result = select_example(configurable(&mut program), number).and_then(|example| {
+            let options = RunOptions {
+                part: example.part(),
+                timeout,
+                ..RunOptions::default()
+            };
+            let source = InputSource::Text(example.input.to_owned());
+            let run = run_day(
+                registration.year,
+                registration.day,
+                Some(&program),
+                &source,
+                &options,
+            );
+            let mut expected = AnswerFile::default();
+            expected.record(registration.day, &Answers::from(&example));
+            Ok(expected.check(registration.day, &run.result?))
+        });
+        match result {
+            Ok(verdicts) => {
+                for (part, verdict) in verdicts {
+                    report.push_str(&format!("Example {} part {}: {}\n", number, part, verdict));
+                }
+            }
+            Err(e) => report.push_str(&format!("Example {}: error: {}\n", number, e)),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        fs::{remove_file, write, File},
+    };
+
+    use super::*;
+
+    fn answers(part1: &str, part2: Option<&str>) -> Answers {
+        Answers {
+            part1: Some(part1.to_owned()),
+            part2: part2.map(|s| s.to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_watcher() {
+        let path = env::temp_dir().join(format!("aoc24-watch-{}.txt", std::process::id()));
+        let _ = remove_file(&path);
+        let mut watcher = Watcher::new([path.clone()]);
+        assert!(watcher.changed().is_empty());
+
+        write(&path, "1").unwrap();
+        assert_eq!(watcher.changed(), vec![path.clone()]);
+        assert!(watcher.changed().is_empty());
+
+        let file = File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+        assert_eq!(watcher.changed(), vec![path.clone()]);
+
+        remove_file(&path).unwrap();
+        assert_eq!(watcher.wait(Duration::ZERO), [path]);
+    }
+
+    #[test]
+    fn test_diff() {
+        let first = answers("11", None);
+        assert_eq!(diff(None, &Ok(answers("11", None))), "Part 1: 11\n");
+        assert_eq!(
+            diff(Some(&first), &Ok(answers("11", Some("31")))),
+            "Part 1: 11\nPart 2: 31 (new)\n"
+        );
+        assert_eq!(
+            diff(
+                Some(&answers("11", Some("31"))),
+                &Ok(answers("12", Some("31")))
+            ),
+            "Part 1: 12 (was 11)\nPart 2: 31\n"
+        );
+        assert_eq!(
+            diff(Some(&first), &Err(Error::unsolvable("stuck"))),
+            "error: no solution: stuck\n"
+        );
+    }
+
+    #[test]
+    fn test_check_examples() {
+        let registration = crate::days::DAYS.iter().find(|r| r.day == 24).unwrap();
+        assert_eq!(
+            check_examples(registration, None),
+            "Example 1 part 1: PASS\n"
+        );
+    }
+}